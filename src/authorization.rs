@@ -1,29 +1,51 @@
 use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::time::SystemTime;
 
-use crate::error::{TokenFetchError, StatesNotEqual};
+use crate::error::{OAuthError, StatesNotEqual, TokenFetchError};
 use crate::model::Token;
 
 use attohttpc::header::AUTHORIZATION;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use sha2::{Digest, Sha256};
 use smallvec::{smallvec, SmallVec};
 use url::Url;
 
-pub struct AuthorizationBuilder<'drop, 'carry_forward, State> {
-    pub(crate) authorization_header: &'carry_forward str,
-    pub(crate) client_id: &'drop str,
+const TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+
+/// Length of generated PKCE code verifiers, must be between 43 and 128.
+const CODE_VERIFIER_LEN: usize = 64;
+
+pub struct AuthorizationBuilder<'drop, 'carry_forward, State, Flow = AuthorizationCode> {
+    pub(crate) authorization_header: Option<&'carry_forward str>,
+    pub(crate) client_id: &'carry_forward str,
     pub(crate) response_type: Option<&'drop str>,
     pub(crate) redirect_uri: Option<Cow<'carry_forward, str>>,
     pub(crate) state: Option<State>,
     pub(crate) scope: Option<String>,
     pub(crate) show_dialog: Option<bool>,
+    pub(crate) code_verifier: Option<String>,
+    pub(crate) flow: PhantomData<Flow>,
 }
 
-pub struct Authorization<'a, State> {
-    authorization_header: &'a str,
+pub struct Authorization<'a, State, Flow = AuthorizationCode> {
+    authorization_header: Option<&'a str>,
+    client_id: &'a str,
     url: Url,
     state: Option<State>,
+    scope: Option<String>,
     redirect_uri: Cow<'a, str>,
+    code_verifier: Option<String>,
+    flow: PhantomData<Flow>,
 }
 
+/// The Authorization Code flow, the code in the redirect is exchanged for a token.
+pub enum AuthorizationCode {}
+
+/// The Implicit Grant flow, the token is returned directly in the redirect uri fragment.
+pub enum ImplicitGrant {}
+
 pub enum NoState {}
 
 impl AsRef<str> for NoState {
@@ -63,45 +85,190 @@ impl<State> Authorization<'_, State> {
         };
 
         if equal {
-            Ok(attohttpc::post("https://accounts.spotify.com/api/token")
-                .form(&serde_json::json!({
-                    "grant_type": "authorization_code",
-                    "code": code,
-                    "redirect_uri": self.redirect_uri.as_ref(),
-                }))?
-                .header(AUTHORIZATION, self.authorization_header)
-                .send()?
-                .json_utf8()?)
+            self.exchange_code(code)
         } else {
             Err(StatesNotEqual.into())
         }
     }
 
-    pub fn refresh_token(&self, token: &Token) -> Option<attohttpc::Result<Token>> {
-        token.refresh_token.as_ref().map(|refresh_token| {
-            attohttpc::post("https://accounts.spotify.com/api/token")
-                .header(AUTHORIZATION, self.authorization_header)
-                .form(&serde_json::json!({
-                    "grant_type": "refresh_token",
-                    "refresh_token": refresh_token,
-                }))?
-                .send()?
-                .json_utf8()
+    /// Fetches a token using the code in the uri the user was redirected to, after verifying
+    /// its state and checking that the user did not deny the authorization.
+    pub fn fetch_token_from_callback(&self, callback: &Url) -> Result<Token, TokenFetchError>
+    where
+        State: AsRef<str>,
+    {
+        self.fetch_token_from_query(callback.query().unwrap_or(""))
+    }
+
+    /// Like [`fetch_token_from_callback`](Self::fetch_token_from_callback), but takes only the
+    /// query string of the callback.
+    pub fn fetch_token_from_query(&self, query: &str) -> Result<Token, TokenFetchError>
+    where
+        State: AsRef<str>,
+    {
+        let params = CallbackParams::parse(query);
+
+        params.verify(self.state.as_ref())?;
+
+        let code = params
+            .get("code")
+            .ok_or(TokenFetchError::MalformedRedirect("code"))?;
+
+        self.exchange_code(code)
+    }
+
+    fn exchange_code(&self, code: &str) -> Result<Token, TokenFetchError> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.redirect_uri.as_ref()),
+        ];
+
+        if let Some(code_verifier) = self.code_verifier.as_ref() {
+            form.push(("code_verifier", code_verifier));
+        }
+
+        self.request_token(form)
+    }
+
+    /// Fetches a new access token using the refresh token of `token`. The refresh token and scope
+    /// of `token` are kept when the response does not include new ones.
+    pub fn refresh_token(&self, token: &Token) -> Result<Token, TokenFetchError> {
+        refresh_token(self.authorization_header, self.client_id, token)
+    }
+
+    fn request_token(&self, form: Vec<(&str, &str)>) -> Result<Token, TokenFetchError> {
+        request_token(self.authorization_header, self.client_id, form)
+    }
+}
+
+impl<State> Authorization<'_, State, ImplicitGrant>
+where
+    State: AsRef<str>,
+{
+    /// Extracts the token from the fragment of the uri the user was redirected to.
+    pub fn token_from_redirect(&self, redirect: &Url) -> Result<Token, TokenFetchError> {
+        let params = CallbackParams::parse(redirect.fragment().unwrap_or(""));
+
+        params.verify(self.state.as_ref())?;
+
+        Ok(Token {
+            access_token: params
+                .get("access_token")
+                .ok_or(TokenFetchError::MalformedRedirect("access_token"))?
+                .to_owned(),
+            token_type: params
+                .get("token_type")
+                .ok_or(TokenFetchError::MalformedRedirect("token_type"))?
+                .to_owned(),
+            expires_in: params
+                .get("expires_in")
+                .and_then(|expires_in| expires_in.parse().ok())
+                .ok_or(TokenFetchError::MalformedRedirect("expires_in"))?,
+            scope: self.scope.clone().unwrap_or_default(),
+            refresh_token: None,
+            obtained_at: SystemTime::now(),
         })
     }
+}
+
+/// The parameters Spotify passes back in the query or fragment of the redirect uri.
+struct CallbackParams<'a>(Vec<(Cow<'a, str>, Cow<'a, str>)>);
+
+impl<'a> CallbackParams<'a> {
+    fn parse(input: &'a str) -> Self {
+        CallbackParams(url::form_urlencoded::parse(input.as_bytes()).collect())
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// Checks that the state matches the one sent in the request, and that the authorization was
+    /// not denied or otherwise failed.
+    fn verify<State>(&self, state: Option<&State>) -> Result<(), TokenFetchError>
+    where
+        State: AsRef<str>,
+    {
+        if self.get("state") != state.map(AsRef::as_ref) {
+            return Err(StatesNotEqual.into());
+        }
 
+        match self.get("error") {
+            None => Ok(()),
+            Some("access_denied") => Err(TokenFetchError::AccessDenied),
+            Some(error) => Err(TokenFetchError::AuthorizationFailed {
+                error: error.to_owned(),
+                description: self.get("error_description").map(str::to_owned),
+            }),
+        }
+    }
+}
+
+impl<State, Flow> Authorization<'_, State, Flow> {
     pub fn url(&self) -> &Url {
         &self.url
     }
+
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
 }
 
 impl<'drop, 'carry_forward, State> AuthorizationBuilder<'drop, 'carry_forward, State> {
+    /// Use the Authorization Code with PKCE flow. A code verifier is generated and its S256
+    /// challenge is sent with the authorization url, the verifier is sent when fetching the token.
+    #[must_use]
+    pub fn pkce(mut self) -> Self {
+        self.code_verifier = Some(code_verifier());
+        self
+    }
+
+    /// Overrides the `code` response type of the Authorization Code flow.
+    #[deprecated(note = "the flow decides the response type, use `implicit_grant` for `token`")]
     #[must_use]
     pub fn response_type(mut self, kind: &'drop str) -> Self {
         self.response_type = Some(kind);
         self
     }
 
+    /// Use the Implicit Grant flow, the token is read from the redirect uri with
+    /// [`Authorization::token_from_redirect`].
+    ///
+    /// The flow decides the response type, it cannot be overridden afterwards:
+    ///
+    /// ```compile_fail
+    /// use spotify_web::{scope::ScopeList, Client};
+    ///
+    /// let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+    ///
+    /// client
+    ///     .authorization()
+    ///     .implicit_grant()
+    ///     .response_type("code");
+    /// ```
+    #[must_use]
+    pub fn implicit_grant(
+        self,
+    ) -> AuthorizationBuilder<'drop, 'carry_forward, State, ImplicitGrant> {
+        AuthorizationBuilder {
+            authorization_header: self.authorization_header,
+            client_id: self.client_id,
+            response_type: Some("token"),
+            redirect_uri: self.redirect_uri,
+            scope: self.scope,
+            show_dialog: self.show_dialog,
+            code_verifier: None,
+            state: self.state,
+            flow: PhantomData,
+        }
+    }
+}
+
+impl<'drop, 'carry_forward, State, Flow> AuthorizationBuilder<'drop, 'carry_forward, State, Flow> {
     #[must_use]
     pub fn redirect_uri(mut self, redirect_uri: impl Into<Cow<'carry_forward, str>>) -> Self {
         self.redirect_uri = Some(redirect_uri.into());
@@ -109,7 +276,7 @@ impl<'drop, 'carry_forward, State> AuthorizationBuilder<'drop, 'carry_forward, S
     }
 
     #[must_use]
-    pub fn state<S>(self, state: S) -> AuthorizationBuilder<'drop, 'carry_forward, S>
+    pub fn state<S>(self, state: S) -> AuthorizationBuilder<'drop, 'carry_forward, S, Flow>
     where
         S: AsRef<str>,
     {
@@ -120,7 +287,9 @@ impl<'drop, 'carry_forward, State> AuthorizationBuilder<'drop, 'carry_forward, S
             redirect_uri: self.redirect_uri,
             scope: self.scope,
             show_dialog: self.show_dialog,
+            code_verifier: self.code_verifier,
             state: Some(state),
+            flow: PhantomData,
         }
     }
 
@@ -131,13 +300,15 @@ impl<'drop, 'carry_forward, State> AuthorizationBuilder<'drop, 'carry_forward, S
     }
 
     #[must_use]
-    pub fn build(self) -> Authorization<'carry_forward, State>
+    pub fn build(self) -> Authorization<'carry_forward, State, Flow>
     where
         State: AsRef<str>,
     {
         let redirect_uri = self.redirect_uri.expect("Redirect uri is a required field");
 
-        let mut params: SmallVec<[_; 6]> = smallvec![
+        let code_challenge = self.code_verifier.as_ref().map(|v| code_challenge(v));
+
+        let mut params: SmallVec<[_; 8]> = smallvec![
             ("client_id", self.client_id),
             (
                 "response_type",
@@ -161,17 +332,107 @@ impl<'drop, 'carry_forward, State> AuthorizationBuilder<'drop, 'carry_forward, S
             params.push(("show_dialog", crate::bool_as_str(show_dialog)));
         }
 
+        if let Some(code_challenge) = code_challenge.as_ref() {
+            params.push(("code_challenge_method", "S256"));
+            params.push(("code_challenge", code_challenge));
+        }
+
         let url = Url::parse_with_params("https://accounts.spotify.com/authorize", params).unwrap();
 
         Authorization {
             authorization_header: self.authorization_header,
+            client_id: self.client_id,
             state: self.state,
+            scope: self.scope,
+            code_verifier: self.code_verifier,
             redirect_uri,
             url,
+            flow: PhantomData,
         }
     }
 }
 
+/// Posts `form` to the token endpoint, authenticating with the client secret if there is one
+/// and with the client id otherwise. Error responses are parsed into [`OAuthError`]s.
+pub(crate) fn request_token<'f>(
+    authorization_header: Option<&str>,
+    client_id: &'f str,
+    mut form: Vec<(&str, &'f str)>,
+) -> Result<Token, TokenFetchError> {
+    let mut req = attohttpc::post(TOKEN_URL);
+
+    match authorization_header {
+        Some(authorization_header) => req = req.header(AUTHORIZATION, authorization_header),
+        None => form.push(("client_id", client_id)),
+    }
+
+    let response = req.form(&form)?.send()?;
+
+    if response.is_success() {
+        Ok(response.json_utf8()?)
+    } else {
+        let status = response.status();
+
+        Err(match response.json_utf8::<OAuthError>() {
+            Ok(error) => error.into(),
+            Err(_) => TokenFetchError::Status(status),
+        })
+    }
+}
+
+pub(crate) fn refresh_token(
+    authorization_header: Option<&str>,
+    client_id: &str,
+    token: &Token,
+) -> Result<Token, TokenFetchError> {
+    let refresh_token = token
+        .refresh_token
+        .as_ref()
+        .ok_or(TokenFetchError::NoRefreshToken)?;
+
+    let refreshed = request_token(
+        authorization_header,
+        client_id,
+        vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ],
+    )?;
+
+    Ok(merge_refreshed(token, refreshed))
+}
+
+/// Spotify may leave out the refresh token, and the scope, from refresh responses, in which case
+/// the previous ones are still valid.
+#[must_use]
+fn merge_refreshed(previous: &Token, mut refreshed: Token) -> Token {
+    if refreshed.refresh_token.is_none() {
+        refreshed.refresh_token = previous.refresh_token.clone();
+    }
+
+    if refreshed.scope.is_empty() {
+        refreshed.scope = previous.scope.clone();
+    }
+
+    refreshed
+}
+
+#[must_use]
+fn code_verifier() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(CODE_VERIFIER_LEN)
+        .collect()
+}
+
+#[must_use]
+fn code_challenge(code_verifier: &str) -> String {
+    base64::encode_config(
+        &Sha256::digest(code_verifier.as_bytes()),
+        base64::URL_SAFE_NO_PAD,
+    )
+}
+
 #[cfg(test)]
 mod test {
     use crate::scope::{ScopeList, UserReadEmail, UserReadPrivate};
@@ -286,9 +547,297 @@ mod test {
             .state("982348434")
             .build();
 
-        assert!(match auth.fetch_token("code", String::from("oijfsklj")) {
-            Err(crate::error::TokenFetchError::SecurityViolation(_)) => true,
-            _ => false,
-        });
+        assert!(matches!(
+            auth.fetch_token("code", String::from("oijfsklj")),
+            Err(crate::error::TokenFetchError::SecurityViolation(_))
+        ));
+    }
+
+    #[test]
+    fn code_challenge_matches_rfc7636() {
+        assert_eq!(
+            super::code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn pkce_auth_url() {
+        let client = Client::public(
+            "5fe01282e44241328a84e7c5cc169165",
+            <scopes![UserReadPrivate]>::create(),
+        );
+
+        let auth = client
+            .authorization()
+            .redirect_uri("http://127.0.0.1:8888/callback")
+            .build();
+
+        let verifier = auth.code_verifier.as_ref().unwrap();
+        assert!(verifier.len() >= 43 && verifier.len() <= 128);
+
+        let challenge = super::code_challenge(verifier);
+        let params: Vec<_> = auth.url().query_pairs().collect();
+
+        assert!(params.contains(&("code_challenge_method".into(), "S256".into())));
+        assert!(params.contains(&("code_challenge".into(), challenge.into())));
+        assert!(auth.authorization_header.is_none());
+    }
+
+    #[test]
+    fn pkce_with_secret() {
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            ScopeList::empty(),
+        );
+
+        let auth = client.authorization().redirect_uri("hello").build();
+        assert!(auth.code_verifier.is_none());
+
+        let auth = client.authorization().redirect_uri("hello").pkce().build();
+        assert!(auth.code_verifier.is_some());
+        assert!(auth.authorization_header.is_some());
+    }
+
+    #[test]
+    fn pkce_token_fetch() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let auth = client.authorization().redirect_uri("hello").build();
+
+        assert!(auth.fetch_token2("code", None).is_err());
+    }
+
+    #[test]
+    fn implicit_grant_url() {
+        let client = Client::public(
+            "5fe01282e44241328a84e7c5cc169165",
+            <scopes![UserReadPrivate]>::create(),
+        );
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .implicit_grant()
+            .state("34fFs29kd09")
+            .build();
+
+        assert_eq!(auth.url().as_ref(), "https://accounts.spotify.com/authorize?client_id=5fe01282e44241328a84e7c5cc169165&response_type=token&redirect_uri=https%3A%2F%2Fexample.com%2Fcallback&state=34fFs29kd09&scope=user-read-private")
+    }
+
+    #[test]
+    fn implicit_grant_token() {
+        let client = Client::public(
+            "5fe01282e44241328a84e7c5cc169165",
+            <scopes![UserReadPrivate, UserReadEmail]>::create(),
+        );
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .implicit_grant()
+            .state("34fFs29kd09")
+            .build();
+
+        let redirect = url::Url::parse("https://example.com/callback#access_token=NwAExz-BVXa&token_type=Bearer&expires_in=3600&state=34fFs29kd09").unwrap();
+        let token = auth.token_from_redirect(&redirect).unwrap();
+
+        assert_eq!(token.access_token, "NwAExz-BVXa");
+        assert_eq!(token.token_type, "Bearer");
+        assert_eq!(token.expires_in, 3600);
+        assert_eq!(token.scope, "user-read-private user-read-email");
+        assert!(token.refresh_token.is_none());
+    }
+
+    #[test]
+    fn implicit_grant_diff_states_errors() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .implicit_grant()
+            .state("34fFs29kd09")
+            .build();
+
+        let redirect = url::Url::parse("https://example.com/callback#access_token=NwAExz-BVXa&token_type=Bearer&expires_in=3600&state=oijfsklj").unwrap();
+
+        assert!(matches!(
+            auth.token_from_redirect(&redirect),
+            Err(crate::error::TokenFetchError::SecurityViolation(_))
+        ));
+
+        let redirect = url::Url::parse("https://example.com/callback#access_token=NwAExz-BVXa&token_type=Bearer&expires_in=3600").unwrap();
+
+        assert!(matches!(
+            auth.token_from_redirect(&redirect),
+            Err(crate::error::TokenFetchError::SecurityViolation(_))
+        ));
+    }
+
+    #[test]
+    fn implicit_grant_malformed() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .implicit_grant()
+            .build();
+
+        let redirect = url::Url::parse(
+            "https://example.com/callback#access_token=NwAExz-BVXa&token_type=Bearer&expires_in=soon",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            auth.token_from_redirect(&redirect),
+            Err(crate::error::TokenFetchError::MalformedRedirect(
+                "expires_in"
+            ))
+        ));
+    }
+
+    #[test]
+    fn callback_access_denied() {
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            ScopeList::empty(),
+        );
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .state("34fFs29kd09")
+            .build();
+
+        let callback =
+            url::Url::parse("https://example.com/callback?error=access_denied&state=34fFs29kd09")
+                .unwrap();
+
+        assert!(matches!(
+            auth.fetch_token_from_callback(&callback),
+            Err(crate::error::TokenFetchError::AccessDenied)
+        ));
+    }
+
+    #[test]
+    fn callback_errors() {
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            ScopeList::empty(),
+        );
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .state("34fFs29kd09")
+            .build();
+
+        assert!(matches!(
+            auth.fetch_token_from_query("code=NApCCg&state=oijfsklj"),
+            Err(crate::error::TokenFetchError::SecurityViolation(_))
+        ));
+
+        assert!(matches!(
+            auth.fetch_token_from_query("error=access_denied&state=oijfsklj"),
+            Err(crate::error::TokenFetchError::SecurityViolation(_))
+        ));
+
+        assert!(matches!(
+            auth.fetch_token_from_query("state=34fFs29kd09"),
+            Err(crate::error::TokenFetchError::MalformedRedirect("code"))
+        ));
+
+        match auth.fetch_token_from_query(
+            "error=server_error&error_description=try+again&state=34fFs29kd09",
+        ) {
+            Err(crate::error::TokenFetchError::AuthorizationFailed { error, description }) => {
+                assert_eq!(error, "server_error");
+                assert_eq!(description.as_deref(), Some("try again"));
+            }
+            _ => panic!("expected authorization failure"),
+        }
+
+        // A matching state goes on to the token exchange, which Spotify rejects for the made up
+        // client, or which fails without network access.
+        assert!(matches!(
+            auth.fetch_token_from_query("code=NApCCg&state=34fFs29kd09"),
+            Err(crate::error::TokenFetchError::OAuth(_))
+                | Err(crate::error::TokenFetchError::Http(_))
+        ));
+    }
+
+    #[test]
+    fn implicit_grant_access_denied() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .implicit_grant()
+            .build();
+
+        let redirect = url::Url::parse("https://example.com/callback#error=access_denied").unwrap();
+
+        assert!(matches!(
+            auth.token_from_redirect(&redirect),
+            Err(crate::error::TokenFetchError::AccessDenied)
+        ));
+    }
+
+    #[test]
+    fn refresh_without_refresh_token() {
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            ScopeList::empty(),
+        );
+
+        let auth = client.authorization().redirect_uri("hello").build();
+
+        let token = crate::model::Token {
+            access_token: String::from("reojwgpoerjg"),
+            token_type: String::from("Bearer"),
+            scope: String::new(),
+            expires_in: 3600,
+            refresh_token: None,
+            obtained_at: std::time::SystemTime::now(),
+        };
+
+        assert!(matches!(
+            auth.refresh_token(&token),
+            Err(crate::error::TokenFetchError::NoRefreshToken)
+        ));
+    }
+
+    #[test]
+    fn refresh_keeps_refresh_token_and_scope() {
+        let previous: crate::model::Token = serde_json::from_str(
+            r#"{"access_token":"NgCXRK","token_type":"Bearer","scope":"user-read-email","expires_in":3600,"refresh_token":"NgAagA"}"#,
+        )
+        .unwrap();
+
+        let refreshed = serde_json::from_str(
+            r#"{"access_token":"NgA6ZcYI","token_type":"Bearer","expires_in":3600}"#,
+        )
+        .unwrap();
+
+        let merged = super::merge_refreshed(&previous, refreshed);
+        assert_eq!(merged.access_token, "NgA6ZcYI");
+        assert_eq!(merged.refresh_token.as_deref(), Some("NgAagA"));
+        assert_eq!(merged.scope, "user-read-email");
+
+        let refreshed = serde_json::from_str(
+            r#"{"access_token":"NgA6ZcYI","token_type":"Bearer","scope":"user-read-private","expires_in":3600,"refresh_token":"AQD2f1"}"#,
+        )
+        .unwrap();
+
+        let merged = super::merge_refreshed(&previous, refreshed);
+        assert_eq!(merged.refresh_token.as_deref(), Some("AQD2f1"));
+        assert_eq!(merged.scope, "user-read-private");
     }
 }
@@ -1,3 +1,6 @@
+use std::fmt;
+
+use serde::Deserialize;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone, Copy, Hash, Eq, PartialEq)]
@@ -8,11 +11,293 @@ pub struct ScopeMismatchError;
 #[error("state from authorization response does not equal state in request")]
 pub struct StatesNotEqual;
 
+/// The `error` code of a failed request to the token endpoint, as defined in RFC 6749 section 5.2.
+#[derive(Deserialize, Debug, Clone, Hash, Eq, PartialEq)]
+#[serde(from = "String")]
+pub enum OAuthErrorCode {
+    InvalidRequest,
+    InvalidClient,
+    InvalidGrant,
+    UnauthorizedClient,
+    UnsupportedGrantType,
+    InvalidScope,
+    Other(String),
+}
+
+impl OAuthErrorCode {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            OAuthErrorCode::InvalidRequest => "invalid_request",
+            OAuthErrorCode::InvalidClient => "invalid_client",
+            OAuthErrorCode::InvalidGrant => "invalid_grant",
+            OAuthErrorCode::UnauthorizedClient => "unauthorized_client",
+            OAuthErrorCode::UnsupportedGrantType => "unsupported_grant_type",
+            OAuthErrorCode::InvalidScope => "invalid_scope",
+            OAuthErrorCode::Other(code) => code,
+        }
+    }
+}
+
+impl From<String> for OAuthErrorCode {
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            "invalid_request" => OAuthErrorCode::InvalidRequest,
+            "invalid_client" => OAuthErrorCode::InvalidClient,
+            "invalid_grant" => OAuthErrorCode::InvalidGrant,
+            "unauthorized_client" => OAuthErrorCode::UnauthorizedClient,
+            "unsupported_grant_type" => OAuthErrorCode::UnsupportedGrantType,
+            "invalid_scope" => OAuthErrorCode::InvalidScope,
+            _ => OAuthErrorCode::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for OAuthErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The body of a failed request to the token endpoint.
+#[derive(Error, Deserialize, Debug, Clone, Hash, Eq, PartialEq)]
+#[error("token request failed with `{error}`")]
+pub struct OAuthError {
+    pub error: OAuthErrorCode,
+    pub error_description: Option<String>,
+}
+
 #[derive(Error, Debug)]
 pub enum TokenFetchError {
     #[error("{0}")]
     Http(#[from] attohttpc::Error),
 
+    #[error("{0}")]
+    OAuth(#[from] OAuthError),
+
+    #[error("token endpoint responded with status {0}")]
+    Status(attohttpc::StatusCode),
+
     #[error("{0}")]
     SecurityViolation(#[from] StatesNotEqual),
+
+    #[error("the client credentials flow requires a client secret")]
+    MissingClientSecret,
+
+    #[error("token has no refresh token")]
+    NoRefreshToken,
+
+    #[error("authorization response is missing or has an invalid `{0}`")]
+    MalformedRedirect(&'static str),
+
+    #[error("user denied the authorization request")]
+    AccessDenied,
+
+    #[error("authorization failed: {error}")]
+    AuthorizationFailed {
+        error: String,
+        description: Option<String>,
+    },
+}
+
+/// The `reason` given by the player endpoints for rejecting a request.
+#[derive(Deserialize, Debug, Clone, Hash, Eq, PartialEq)]
+#[serde(from = "String")]
+pub enum PlayerErrorReason {
+    NoPrevTrack,
+    NoNextTrack,
+    NoSpecificTrack,
+    AlreadyPaused,
+    NotPaused,
+    NotPlayingLocally,
+    NotPlayingTrack,
+    NotPlayingContext,
+    EndlessContext,
+    ContextDisallow,
+    AlreadyPlaying,
+    RateLimited,
+    RemoteControlDisallow,
+    DeviceNotControllable,
+    VolumeControlDisallow,
+    NoActiveDevice,
+    PremiumRequired,
+    Unknown,
+    Other(String),
+}
+
+impl From<String> for PlayerErrorReason {
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "NO_PREV_TRACK" => PlayerErrorReason::NoPrevTrack,
+            "NO_NEXT_TRACK" => PlayerErrorReason::NoNextTrack,
+            "NO_SPECIFIC_TRACK" => PlayerErrorReason::NoSpecificTrack,
+            "ALREADY_PAUSED" => PlayerErrorReason::AlreadyPaused,
+            "NOT_PAUSED" => PlayerErrorReason::NotPaused,
+            "NOT_PLAYING_LOCALLY" => PlayerErrorReason::NotPlayingLocally,
+            "NOT_PLAYING_TRACK" => PlayerErrorReason::NotPlayingTrack,
+            "NOT_PLAYING_CONTEXT" => PlayerErrorReason::NotPlayingContext,
+            "ENDLESS_CONTEXT" => PlayerErrorReason::EndlessContext,
+            "CONTEXT_DISALLOW" => PlayerErrorReason::ContextDisallow,
+            "ALREADY_PLAYING" => PlayerErrorReason::AlreadyPlaying,
+            "RATE_LIMITED" => PlayerErrorReason::RateLimited,
+            "REMOTE_CONTROL_DISALLOW" => PlayerErrorReason::RemoteControlDisallow,
+            "DEVICE_NOT_CONTROLLABLE" => PlayerErrorReason::DeviceNotControllable,
+            "VOLUME_CONTROL_DISALLOW" => PlayerErrorReason::VolumeControlDisallow,
+            "NO_ACTIVE_DEVICE" => PlayerErrorReason::NoActiveDevice,
+            "PREMIUM_REQUIRED" => PlayerErrorReason::PremiumRequired,
+            "UNKNOWN" => PlayerErrorReason::Unknown,
+            _ => PlayerErrorReason::Other(reason),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("{0}")]
+    Http(#[from] attohttpc::Error),
+
+    #[error("{0}")]
+    Token(#[from] TokenFetchError),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{message} ({status})")]
+    Api {
+        status: u16,
+        message: String,
+        reason: Option<PlayerErrorReason>,
+    },
+
+    #[error("endpoint responded with status {0}")]
+    Status(attohttpc::StatusCode),
+
+    #[error("volume must be between 0 and 100, got {0}")]
+    InvalidVolume(u8),
+}
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    error: ApiErrorObject,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorObject {
+    status: u16,
+    message: String,
+    reason: Option<PlayerErrorReason>,
+}
+
+impl From<ApiErrorObject> for ApiError {
+    fn from(error: ApiErrorObject) -> Self {
+        ApiError::Api {
+            status: error.status,
+            message: error.message,
+            reason: error.reason,
+        }
+    }
+}
+
+/// Passes successful responses through, and parses the error object of unsuccessful ones.
+pub(crate) fn error_for_status(
+    response: attohttpc::Response,
+) -> Result<attohttpc::Response, ApiError> {
+    if response.is_success() {
+        Ok(response)
+    } else {
+        let status = response.status();
+
+        Err(match response.json_utf8::<ApiErrorBody>() {
+            Ok(body) => body.error.into(),
+            Err(_) => ApiError::Status(status),
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TokenStoreError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(feature = "loopback")]
+#[derive(Error, Debug)]
+pub enum LoopbackError {
+    #[error("redirect uri is not a loopback address with an explicit port")]
+    NotLoopback,
+
+    #[error("timed out waiting for the authorization callback")]
+    TimedOut,
+
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Token(#[from] TokenFetchError),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn oauth_error_codes() {
+        let error: OAuthError = serde_json::from_str(
+            r#"{"error":"invalid_grant","error_description":"Invalid authorization code"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(error.error, OAuthErrorCode::InvalidGrant);
+        assert_eq!(
+            error.error_description.as_deref(),
+            Some("Invalid authorization code")
+        );
+
+        let error: OAuthError =
+            serde_json::from_str(r#"{"error":"temporarily_unavailable"}"#).unwrap();
+
+        assert_eq!(
+            error.error,
+            OAuthErrorCode::Other(String::from("temporarily_unavailable"))
+        );
+        assert_eq!(error.error.as_str(), "temporarily_unavailable");
+        assert!(error.error_description.is_none());
+    }
+
+    #[test]
+    fn api_error_reasons() {
+        let body: ApiErrorBody = serde_json::from_str(
+            r#"{"error":{"status":404,"message":"Player command failed: No active device found","reason":"NO_ACTIVE_DEVICE"}}"#,
+        )
+        .unwrap();
+
+        match ApiError::from(body.error) {
+            ApiError::Api {
+                status,
+                message,
+                reason,
+            } => {
+                assert_eq!(status, 404);
+                assert_eq!(message, "Player command failed: No active device found");
+                assert_eq!(reason, Some(PlayerErrorReason::NoActiveDevice));
+            }
+            _ => panic!("expected api error"),
+        }
+
+        let body: ApiErrorBody = serde_json::from_str(
+            r#"{"error":{"status":401,"message":"The access token expired"}}"#,
+        )
+        .unwrap();
+
+        assert!(body.error.reason.is_none());
+
+        let reason: PlayerErrorReason = serde_json::from_str(r#""PREMIUM_REQUIRED""#).unwrap();
+        assert_eq!(reason, PlayerErrorReason::PremiumRequired);
+
+        let reason: PlayerErrorReason = serde_json::from_str(r#""NEW_REASON""#).unwrap();
+        assert_eq!(reason, PlayerErrorReason::Other(String::from("NEW_REASON")));
+    }
 }
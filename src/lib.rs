@@ -1,14 +1,25 @@
 #![feature(specialization)]
-use error::ScopeMismatchError;
+#![allow(incomplete_features)]
+use error::{ScopeMismatchError, TokenFetchError};
 use model::Token;
 use scope::*;
+use session::Session;
+use std::sync::RwLock;
 
 pub mod authorization;
 pub mod error;
+mod library;
+#[cfg(feature = "loopback")]
+pub mod loopback;
 pub mod model;
+mod paging;
 pub mod scope;
+mod session;
 mod spotify;
+pub mod store;
 
+pub use library::*;
+pub use paging::*;
 pub use spotify::*;
 
 mod private {
@@ -20,12 +31,13 @@ mod private {
 
 pub struct Client<'a, Scopes> {
     id: &'a str,
-    authorization_header: String,
+    authorization_header: Option<String>,
     scopes: Scopes,
 }
 
 pub struct Spotify<Scopes> {
-    authorization_header: String,
+    authorization_header: RwLock<String>,
+    session: Option<Session>,
     _scopes: Scopes,
 }
 
@@ -37,7 +49,7 @@ where
         Self {
             id,
             scopes,
-            authorization_header: {
+            authorization_header: Some({
                 // TODO: Optimize base64 with streaming api?
                 let mut header = String::from("Basic ");
 
@@ -48,7 +60,17 @@ where
                 );
 
                 header
-            },
+            }),
+        }
+    }
+
+    /// Creates a client without a secret, for applications that cannot keep one confidential.
+    /// Authorizations made by such a client always use the Authorization Code with PKCE flow.
+    pub fn public(id: &'a str, scopes: ScopeList<Scopes>) -> Self {
+        Self {
+            id,
+            scopes,
+            authorization_header: None,
         }
     }
 
@@ -59,6 +81,43 @@ where
     where
         Scopes: Copy,
         <ScopeList<Scopes> as ScopeListBehaviour>::TypeList: AccumulateTypeMap<bool> + Length,
+    {
+        Self::verify_scopes(token)?;
+
+        Ok(Spotify {
+            _scopes: self.scopes,
+            authorization_header: RwLock::new(bearer(token)),
+            session: None,
+        })
+    }
+
+    /// Like [`with_access_token`](Self::with_access_token), but the handle owns the token and
+    /// refreshes it shortly before it expires, or when a request is rejected as unauthorized.
+    /// Register [`Spotify::on_token_refresh`] to be notified of the refreshed tokens.
+    pub fn with_refreshing_token(
+        &self,
+        token: Token,
+    ) -> Result<Spotify<ScopeList<Scopes>>, ScopeMismatchError>
+    where
+        Scopes: Copy,
+        <ScopeList<Scopes> as ScopeListBehaviour>::TypeList: AccumulateTypeMap<bool> + Length,
+    {
+        Self::verify_scopes(&token)?;
+
+        Ok(Spotify {
+            _scopes: self.scopes,
+            authorization_header: RwLock::new(bearer(&token)),
+            session: Some(Session::new(
+                self.id,
+                self.authorization_header.clone(),
+                token,
+            )),
+        })
+    }
+
+    fn verify_scopes(token: &Token) -> Result<(), ScopeMismatchError>
+    where
+        <ScopeList<Scopes> as ScopeListBehaviour>::TypeList: AccumulateTypeMap<bool> + Length,
     {
         let mut available = <ScopeList<Scopes>>::type_map();
 
@@ -71,39 +130,81 @@ where
         }
 
         if available.values().all(|x| *x) {
-            const BEARER: &'static str = "Bearer ";
-
-            let mut ah = String::with_capacity(BEARER.len() + token.access_token.len());
-
-            ah.push_str(BEARER);
-            ah.push_str(&token.access_token);
-
-            Ok(Spotify {
-                _scopes: self.scopes,
-                authorization_header: ah,
-            })
+            Ok(())
         } else {
             Err(ScopeMismatchError)
         }
     }
 
+    /// Fetches an app-only token with the Client Credentials flow. The handle carries no user
+    /// scopes, so only endpoints that do not access user data are available on it.
+    ///
+    /// ```compile_fail
+    /// # use spotify_web::{scope::ScopeList, Client};
+    /// let client = Client::new("client id", "client secret", ScopeList::empty());
+    /// let spotify = client.client_credentials().unwrap();
+    ///
+    /// // There is no user behind an app-only token.
+    /// spotify.current_user();
+    /// ```
+    pub fn client_credentials(&self) -> Result<Spotify<AppOnly>, TokenFetchError> {
+        let authorization_header = self
+            .authorization_header
+            .as_deref()
+            .ok_or(TokenFetchError::MissingClientSecret)?;
+
+        let token = authorization::request_token(
+            Some(authorization_header),
+            self.id,
+            vec![("grant_type", "client_credentials")],
+        )?;
+
+        Ok(Spotify {
+            _scopes: AppOnly,
+            authorization_header: RwLock::new(bearer(&token)),
+            session: None,
+        })
+    }
+
     #[must_use]
-    pub fn authorization(&self) -> authorization::AuthorizationBuilder<authorization::NoState>
+    pub fn authorization(
+        &self,
+    ) -> authorization::AuthorizationBuilder<'_, '_, authorization::NoState>
     where
         <ScopeList<Scopes> as ScopeListBehaviour>::TypeList: AccumulateScopeName,
     {
-        authorization::AuthorizationBuilder {
-            authorization_header: &self.authorization_header,
-            client_id: self.id.as_ref(),
+        let builder = authorization::AuthorizationBuilder {
+            authorization_header: self.authorization_header.as_deref(),
+            client_id: self.id,
             scope: <ScopeList<Scopes>>::joined_names(),
             response_type: Default::default(),
             redirect_uri: Default::default(),
             state: Default::default(),
             show_dialog: Default::default(),
+            code_verifier: Default::default(),
+            flow: Default::default(),
+        };
+
+        if self.authorization_header.is_none() {
+            builder.pkce()
+        } else {
+            builder
         }
     }
 }
 
+#[must_use]
+fn bearer(token: &Token) -> String {
+    const BEARER: &str = "Bearer ";
+
+    let mut ah = String::with_capacity(BEARER.len() + token.access_token.len());
+
+    ah.push_str(BEARER);
+    ah.push_str(&token.access_token);
+
+    ah
+}
+
 #[must_use]
 fn bool_as_str(value: bool) -> &'static str {
     if value {
@@ -127,6 +228,7 @@ mod test {
             scope: Scope::joined_names().unwrap(),
             expires_in: 3,
             refresh_token: None,
+            obtained_at: std::time::SystemTime::now(),
         };
 
         let client = Client::new(
@@ -141,6 +243,58 @@ mod test {
         spotify.pause_playback(None).ok();
     }
 
+    #[test]
+    fn current_user_needs_no_scopes() {
+        type Scope = scopes![];
+
+        let token = &Token {
+            access_token: String::from("reojwgpoerjg"),
+            token_type: String::from("Bearer"),
+            scope: String::new(),
+            expires_in: 3600,
+            refresh_token: None,
+            obtained_at: std::time::SystemTime::now(),
+        };
+
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            Scope::create(),
+        );
+
+        let spotify = client.with_access_token(token).unwrap();
+
+        // TODO: mock api
+        spotify.current_user().ok();
+    }
+
+    #[test]
+    fn volume_out_of_range() {
+        type Scope = scopes![UserModifyPlaybackState];
+
+        let token = &Token {
+            access_token: String::from("reojwgpoerjg"),
+            token_type: String::from("Bearer"),
+            scope: Scope::joined_names().unwrap(),
+            expires_in: 3600,
+            refresh_token: None,
+            obtained_at: std::time::SystemTime::now(),
+        };
+
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            Scope::create(),
+        );
+
+        let spotify = client.with_access_token(token).unwrap();
+
+        assert!(matches!(
+            spotify.set_volume(101, None),
+            Err(error::ApiError::InvalidVolume(101))
+        ));
+    }
+
     #[test]
     fn scopes_mismatch() {
         type Scope = scopes![UserReadEmail, UserReadPrivate];
@@ -151,6 +305,7 @@ mod test {
             scope: String::from("user-read-email ldfkjwefw eflkjwe ;flwe;qf"),
             expires_in: 3,
             refresh_token: None,
+            obtained_at: std::time::SystemTime::now(),
         };
 
         let client = Client::new(
@@ -161,4 +316,94 @@ mod test {
 
         assert!(client.with_access_token(token).is_err());
     }
+
+    #[test]
+    fn client_credentials() {
+        fn app_only(spotify: Result<Spotify<AppOnly>, TokenFetchError>) {
+            // Unscoped endpoints are available on app-only handles.
+            if let Ok(spotify) = spotify {
+                spotify.user_profile("wizzler").ok();
+            }
+        }
+
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            <scopes![UserReadEmail]>::create(),
+        );
+
+        let spotify = client.client_credentials();
+        assert!(spotify.is_err());
+
+        app_only(spotify);
+    }
+
+    #[test]
+    fn client_credentials_requires_secret() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        assert!(matches!(
+            client.client_credentials(),
+            Err(TokenFetchError::MissingClientSecret)
+        ));
+    }
+
+    #[test]
+    fn refreshing_token() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        type Scope = scopes![UserModifyPlaybackState];
+
+        let token = Token {
+            access_token: String::from("reojwgpoerjg"),
+            token_type: String::from("Bearer"),
+            scope: Scope::joined_names().unwrap(),
+            expires_in: 3600,
+            refresh_token: Some(String::from("wefwfjwoeif")),
+            obtained_at: std::time::SystemTime::UNIX_EPOCH,
+        };
+
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            Scope::create(),
+        );
+
+        let refreshed = Arc::new(AtomicBool::new(false));
+        let on_refresh = refreshed.clone();
+
+        let spotify = client
+            .with_refreshing_token(token)
+            .unwrap()
+            .on_token_refresh(move |_| on_refresh.store(true, Ordering::SeqCst));
+
+        assert_eq!(spotify.token().unwrap().access_token, "reojwgpoerjg");
+
+        // The expired token is refreshed first, which fails with the made up credentials.
+        assert!(matches!(
+            spotify.pause_playback(None),
+            Err(error::ApiError::Token(_))
+        ));
+        assert!(!refreshed.load(Ordering::SeqCst));
+        assert_eq!(spotify.token().unwrap().access_token, "reojwgpoerjg");
+    }
+
+    #[test]
+    fn refreshing_token_scopes_mismatch() {
+        type Scope = scopes![UserReadEmail, UserReadPrivate];
+
+        let token = Token {
+            access_token: String::from("reojwgpoerjg"),
+            token_type: String::from("Bearer"),
+            scope: String::from("user-read-email"),
+            expires_in: 3600,
+            refresh_token: Some(String::from("wefwfjwoeif")),
+            obtained_at: std::time::SystemTime::now(),
+        };
+
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", Scope::create());
+
+        assert!(client.with_refreshing_token(token).is_err());
+    }
 }
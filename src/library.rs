@@ -0,0 +1,260 @@
+use attohttpc::header::{AUTHORIZATION, CONTENT_LENGTH};
+use attohttpc::{Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+
+use crate::error::ApiError;
+use crate::model::{Paging, SavedAlbum, SavedEpisode, SavedShow, SavedTrack, SimplifiedAudiobook};
+use crate::scope::*;
+use crate::{Page, Spotify};
+
+/// A kind of item that can be saved in the user's library, such as [`Albums`], for use with the
+/// generic library methods like [`Spotify::saved`].
+pub trait LibraryItem: crate::private::Sealed {
+    /// The path of the library endpoints of this kind below `/v1/me/`.
+    const PATH: &'static str;
+
+    /// The most ids the save, remove and contains endpoints of this kind accept in a single
+    /// request, longer lists are sent in chunks.
+    const MAX_IDS: usize;
+
+    /// How Spotify lists saved items of this kind.
+    type Saved: DeserializeOwned;
+}
+
+/// Tracks in the user's library.
+#[derive(Debug, Copy, Clone)]
+pub struct Tracks;
+
+impl crate::private::Sealed for Tracks {}
+
+impl LibraryItem for Tracks {
+    const PATH: &'static str = "tracks";
+    const MAX_IDS: usize = 50;
+    type Saved = SavedTrack;
+}
+
+/// Albums in the user's library.
+#[derive(Debug, Copy, Clone)]
+pub struct Albums;
+
+impl crate::private::Sealed for Albums {}
+
+impl LibraryItem for Albums {
+    const PATH: &'static str = "albums";
+    const MAX_IDS: usize = 20;
+    type Saved = SavedAlbum;
+}
+
+/// Podcast shows in the user's library.
+#[derive(Debug, Copy, Clone)]
+pub struct Shows;
+
+impl crate::private::Sealed for Shows {}
+
+impl LibraryItem for Shows {
+    const PATH: &'static str = "shows";
+    const MAX_IDS: usize = 50;
+    type Saved = SavedShow;
+}
+
+/// Podcast episodes in the user's library.
+#[derive(Debug, Copy, Clone)]
+pub struct Episodes;
+
+impl crate::private::Sealed for Episodes {}
+
+impl LibraryItem for Episodes {
+    const PATH: &'static str = "episodes";
+    const MAX_IDS: usize = 50;
+    type Saved = SavedEpisode;
+}
+
+/// Audiobooks in the user's library.
+#[derive(Debug, Copy, Clone)]
+pub struct Audiobooks;
+
+impl crate::private::Sealed for Audiobooks {}
+
+impl LibraryItem for Audiobooks {
+    const PATH: &'static str = "audiobooks";
+    const MAX_IDS: usize = 50;
+    type Saved = SimplifiedAudiobook;
+}
+
+impl<Scopes> Spotify<Scopes>
+where
+    Scopes: Scoped<UserLibraryRead>,
+{
+    /// The tracks saved in the user's library, most recently saved first. Spotify defaults to a
+    /// limit of 20, at most 50.
+    pub fn saved_tracks<'a>(
+        &self,
+        market: impl Into<Option<&'a str>>,
+        limit: impl Into<Option<u32>>,
+        offset: impl Into<Option<u32>>,
+    ) -> Result<Page<'_, Scopes, SavedTrack>, ApiError> {
+        self.saved::<Tracks>(market, limit, offset)
+    }
+
+    /// Whether each of the tracks with the ids `ids` is saved in the user's library.
+    pub fn saved_tracks_contains(&self, ids: &[&str]) -> Result<Vec<bool>, ApiError> {
+        self.saved_contains::<Tracks>(ids)
+    }
+
+    /// The items of kind `K` saved in the user's library, most recently saved first. Spotify
+    /// defaults to a limit of 20, at most 50.
+    pub fn saved<'a, K>(
+        &self,
+        market: impl Into<Option<&'a str>>,
+        limit: impl Into<Option<u32>>,
+        offset: impl Into<Option<u32>>,
+    ) -> Result<Page<'_, Scopes, K::Saved>, ApiError>
+    where
+        K: LibraryItem,
+    {
+        let url = format!("https://api.spotify.com/v1/me/{}", K::PATH);
+        let market = market.into();
+        let limit = limit.into();
+        let offset = offset.into();
+
+        let page: Paging<K::Saved> = self
+            .send(|authorization| {
+                let mut req = attohttpc::get(&url).header(AUTHORIZATION, authorization);
+
+                if let Some(market) = market {
+                    req = req.param("market", market);
+                }
+
+                if let Some(limit) = limit {
+                    req = req.param("limit", limit);
+                }
+
+                if let Some(offset) = offset {
+                    req = req.param("offset", offset);
+                }
+
+                req.send()
+            })?
+            .json_utf8()?;
+
+        Ok(Page::new(self, page))
+    }
+
+    /// Whether each of the items of kind `K` with the ids `ids` is saved in the user's library.
+    pub fn saved_contains<K>(&self, ids: &[&str]) -> Result<Vec<bool>, ApiError>
+    where
+        K: LibraryItem,
+    {
+        let url = format!("https://api.spotify.com/v1/me/{}/contains", K::PATH);
+        let mut contains = Vec::with_capacity(ids.len());
+
+        for ids in chunked_ids(ids, K::MAX_IDS) {
+            let chunk: Vec<bool> = self
+                .send(|authorization| {
+                    attohttpc::get(&url)
+                        .header(AUTHORIZATION, authorization)
+                        .param("ids", &ids)
+                        .send()
+                })?
+                .json_utf8()?;
+
+            contains.extend(chunk);
+        }
+
+        Ok(contains)
+    }
+}
+
+impl<Scopes> Spotify<Scopes>
+where
+    Scopes: Scoped<UserLibraryModify>,
+{
+    /// Saves the tracks with the ids `ids` to the user's library.
+    pub fn save_tracks(&self, ids: &[&str]) -> Result<(), ApiError> {
+        self.save::<Tracks>(ids)
+    }
+
+    /// Removes the tracks with the ids `ids` from the user's library.
+    pub fn remove_saved_tracks(&self, ids: &[&str]) -> Result<(), ApiError> {
+        self.remove_saved::<Tracks>(ids)
+    }
+
+    /// Saves the items of kind `K` with the ids `ids` to the user's library.
+    pub fn save<K>(&self, ids: &[&str]) -> Result<(), ApiError>
+    where
+        K: LibraryItem,
+    {
+        self.library_modify::<K>(Method::PUT, ids)
+    }
+
+    /// Removes the items of kind `K` with the ids `ids` from the user's library.
+    pub fn remove_saved<K>(&self, ids: &[&str]) -> Result<(), ApiError>
+    where
+        K: LibraryItem,
+    {
+        self.library_modify::<K>(Method::DELETE, ids)
+    }
+
+    fn library_modify<K>(&self, method: Method, ids: &[&str]) -> Result<(), ApiError>
+    where
+        K: LibraryItem,
+    {
+        let url = format!("https://api.spotify.com/v1/me/{}", K::PATH);
+
+        for ids in chunked_ids(ids, K::MAX_IDS) {
+            self.send(|authorization| {
+                RequestBuilder::new(method.clone(), &url)
+                    .header(AUTHORIZATION, authorization)
+                    .header(CONTENT_LENGTH, 0)
+                    .param("ids", &ids)
+                    .send()
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Comma separated lists of at most `max` ids each.
+fn chunked_ids<'a>(ids: &'a [&str], max: usize) -> impl Iterator<Item = String> + 'a {
+    ids.chunks(max).map(|chunk| chunk.join(","))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ids(count: usize) -> Vec<String> {
+        (0..count).map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn chunks_ids() {
+        let ids = ids(120);
+        let ids = ids.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let chunks = chunked_ids(&ids, Tracks::MAX_IDS).collect::<Vec<_>>();
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].split(',').count(), 50);
+        assert_eq!(chunks[1].split(',').next(), Some("50"));
+        assert_eq!(chunks[2].split(',').count(), 20);
+        assert_eq!(chunks[2].split(',').next_back(), Some("119"));
+
+        assert_eq!(chunked_ids(&[], Tracks::MAX_IDS).count(), 0);
+    }
+
+    #[test]
+    fn chunks_album_ids() {
+        let ids = ids(45);
+        let ids = ids.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let chunks = chunked_ids(&ids, Albums::MAX_IDS).collect::<Vec<_>>();
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].split(',').count(), 20);
+        assert_eq!(chunks[1].split(',').next(), Some("20"));
+        assert_eq!(chunks[2].split(',').count(), 5);
+        assert_eq!(chunks[2].split(',').next_back(), Some("44"));
+    }
+}
@@ -0,0 +1,430 @@
+use std::borrow::Cow;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use url::{Host, Url};
+
+use crate::authorization::Authorization;
+use crate::error::LoopbackError;
+use crate::model::Token;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long to wait for the request on an accepted connection. Browsers open connections to
+/// loopback hosts that never carry a request, waiting on those must not hold up the callback.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The most bytes read of a request head, generous for the cookies browsers send to localhost.
+const MAX_REQUEST_HEAD: u64 = 64 * 1024;
+
+const DEFAULT_SUCCESS_PAGE: &str = "<!DOCTYPE html><html><body><h1>Authorization complete</h1>\
+    <p>You can close this window and return to the application.</p></body></html>";
+
+const DEFAULT_FAILURE_PAGE: &str = "<!DOCTYPE html><html><body><h1>Authorization failed</h1>\
+    <p>You can close this window and return to the application.</p></body></html>";
+
+/// A listener on the loopback redirect uri of an [`Authorization`], waiting for the single
+/// callback request the browser makes after the user has answered the consent dialog.
+pub struct Loopback<'auth, 'a, State> {
+    authorization: &'auth Authorization<'a, State>,
+    listener: TcpListener,
+    redirect_uri: Url,
+    timeout: Duration,
+    success_page: Cow<'static, str>,
+    failure_page: Cow<'static, str>,
+}
+
+impl<'a, State> Authorization<'a, State> {
+    /// Binds the address of the redirect uri, which has to be a loopback address with an
+    /// explicit port such as `http://127.0.0.1:8888/callback`.
+    ///
+    /// Bind before sending the user to the authorization url, so the callback cannot be missed.
+    pub fn loopback(&self) -> Result<Loopback<'_, 'a, State>, LoopbackError> {
+        let redirect_uri =
+            Url::parse(self.redirect_uri()).map_err(|_| LoopbackError::NotLoopback)?;
+
+        let ip = match redirect_uri.host() {
+            Some(Host::Ipv4(ip)) if ip.is_loopback() => IpAddr::V4(ip),
+            Some(Host::Ipv6(ip)) if ip.is_loopback() => IpAddr::V6(ip),
+            Some(Host::Domain("localhost")) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            _ => return Err(LoopbackError::NotLoopback),
+        };
+
+        let port = redirect_uri.port().ok_or(LoopbackError::NotLoopback)?;
+
+        let listener = TcpListener::bind((ip, port))?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Loopback {
+            authorization: self,
+            listener,
+            redirect_uri,
+            timeout: DEFAULT_TIMEOUT,
+            success_page: Cow::Borrowed(DEFAULT_SUCCESS_PAGE),
+            failure_page: Cow::Borrowed(DEFAULT_FAILURE_PAGE),
+        })
+    }
+}
+
+impl<State> Loopback<'_, '_, State> {
+    /// How long to wait for the callback, defaults to five minutes.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The html shown in the browser when a token was fetched.
+    #[must_use]
+    pub fn success_page(mut self, html: impl Into<Cow<'static, str>>) -> Self {
+        self.success_page = html.into();
+        self
+    }
+
+    /// The html shown in the browser when the authorization was denied or failed.
+    #[must_use]
+    pub fn failure_page(mut self, html: impl Into<Cow<'static, str>>) -> Self {
+        self.failure_page = html.into();
+        self
+    }
+
+    /// Waits for the callback and fetches a token with
+    /// [`Authorization::fetch_token_from_callback`]. Requests to other paths than the one of the
+    /// redirect uri, such as `/favicon.ico`, are answered with `404 Not Found` and ignored.
+    pub fn fetch_token(self) -> Result<Token, LoopbackError>
+    where
+        State: AsRef<str>,
+    {
+        let deadline = Instant::now() + self.timeout;
+
+        loop {
+            let mut stream = self.accept(deadline)?;
+
+            let callback = match self.read_request(&mut stream, deadline) {
+                Ok(callback) => callback,
+                // The browser gave up on this connection or never sent a request on it, keep
+                // waiting for another.
+                Err(_) => continue,
+            };
+
+            match callback {
+                Some(callback) => {
+                    let token = self.authorization.fetch_token_from_callback(&callback);
+
+                    let page = match token {
+                        Ok(_) => &self.success_page,
+                        Err(_) => &self.failure_page,
+                    };
+
+                    respond(&mut stream, "200 OK", page).ok();
+
+                    return Ok(token?);
+                }
+                None => {
+                    respond(&mut stream, "404 Not Found", "").ok();
+                }
+            }
+        }
+    }
+
+    fn accept(&self, deadline: Instant) -> Result<TcpStream, LoopbackError> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(false)?;
+                    return Ok(stream);
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Err(LoopbackError::TimedOut);
+                    }
+
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Reads the request head, returning the full callback url if the request was for the path
+    /// of the redirect uri. Gives up after [`REQUEST_TIMEOUT`] or at `deadline`, whichever comes
+    /// first, no matter how slowly the client keeps sending.
+    fn read_request(&self, stream: &mut TcpStream, deadline: Instant) -> io::Result<Option<Url>> {
+        let deadline = deadline.min(Instant::now() + REQUEST_TIMEOUT);
+        let mut reader = BufReader::new(Read::take(&*stream, MAX_REQUEST_HEAD));
+
+        let request_line = read_line(&mut reader, deadline)?;
+
+        // Drain the headers, the callback only carries data in the request target.
+        while read_line(&mut reader, deadline)?.len() > 2 {}
+
+        let target = match request_line.split(' ').nth(1) {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+
+        Ok(self
+            .redirect_uri
+            .join(target)
+            .ok()
+            .filter(|callback| callback.path() == self.redirect_uri.path()))
+    }
+}
+
+/// Reads a line including its `\n`, failing once `deadline` passes or the limit of the reader
+/// is reached.
+fn read_line(
+    reader: &mut BufReader<io::Take<&TcpStream>>,
+    deadline: Instant,
+) -> io::Result<String> {
+    let mut line = Vec::new();
+
+    loop {
+        let remaining = deadline
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| *remaining > Duration::from_millis(0))
+            .ok_or_else(|| io::Error::from(io::ErrorKind::TimedOut))?;
+
+        reader
+            .get_ref()
+            .get_ref()
+            .set_read_timeout(Some(remaining))?;
+
+        let buf = reader.fill_buf()?;
+
+        if buf.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        match buf.iter().position(|byte| *byte == b'\n') {
+            Some(end) => {
+                line.extend_from_slice(&buf[..=end]);
+                reader.consume(end + 1);
+                break;
+            }
+            None => {
+                let len = buf.len();
+                line.extend_from_slice(buf);
+                reader.consume(len);
+            }
+        }
+    }
+
+    String::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+
+    stream.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+
+    use crate::error::{LoopbackError, TokenFetchError};
+    use crate::scope::ScopeList;
+    use crate::Client;
+
+    fn free_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    fn request(port: u16, target: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\n\r\n",
+            target, port
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn receives_callback() {
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            ScopeList::empty(),
+        );
+
+        let port = free_port();
+
+        let auth = client
+            .authorization()
+            .redirect_uri(format!("http://127.0.0.1:{}/callback", port))
+            .state("34fFs29kd09")
+            .build();
+
+        let loopback = auth
+            .loopback()
+            .unwrap()
+            .timeout(Duration::from_secs(10))
+            .failure_page("<p>denied</p>");
+
+        let browser = std::thread::spawn(move || {
+            let favicon = request(port, "/favicon.ico");
+            let callback = request(port, "/callback?error=access_denied&state=34fFs29kd09");
+
+            (favicon, callback)
+        });
+
+        assert!(matches!(
+            loopback.fetch_token(),
+            Err(LoopbackError::Token(TokenFetchError::AccessDenied))
+        ));
+
+        let (favicon, callback) = browser.join().unwrap();
+
+        assert!(favicon.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(callback.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(callback.ends_with("\r\n\r\n<p>denied</p>"));
+    }
+
+    #[test]
+    fn skips_idle_connections() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let port = free_port();
+
+        let auth = client
+            .authorization()
+            .redirect_uri(format!("http://127.0.0.1:{}/callback", port))
+            .state("34fFs29kd09")
+            .build();
+
+        let loopback = auth.loopback().unwrap().timeout(Duration::from_secs(20));
+
+        // A speculative connection that never sends a request, opened before the callback.
+        let idle = TcpStream::connect(("127.0.0.1", port)).unwrap();
+
+        let browser = std::thread::spawn(move || {
+            request(port, "/callback?error=access_denied&state=34fFs29kd09")
+        });
+
+        let started = Instant::now();
+
+        assert!(matches!(
+            loopback.fetch_token(),
+            Err(LoopbackError::Token(TokenFetchError::AccessDenied))
+        ));
+
+        // Served once the idle connection is given up on, long before the timeout.
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        assert!(browser.join().unwrap().starts_with("HTTP/1.1 200 OK\r\n"));
+
+        drop(idle);
+    }
+
+    #[test]
+    fn skips_slow_and_oversized_requests() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let port = free_port();
+
+        let auth = client
+            .authorization()
+            .redirect_uri(format!("http://127.0.0.1:{}/callback", port))
+            .state("34fFs29kd09")
+            .build();
+
+        let loopback = auth.loopback().unwrap().timeout(Duration::from_secs(20));
+
+        // A client that keeps sending, but too slowly to ever finish its request.
+        let mut slow = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        std::thread::spawn(move || {
+            for _ in 0..40 {
+                if slow.write_all(b"G").is_err() {
+                    break;
+                }
+
+                std::thread::sleep(Duration::from_millis(500));
+            }
+        });
+
+        // A request line without end.
+        let mut oversized = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        std::thread::spawn(move || {
+            let chunk = [b'a'; 1024];
+
+            for _ in 0..128 {
+                if oversized.write_all(&chunk).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let browser = std::thread::spawn(move || {
+            request(port, "/callback?error=access_denied&state=34fFs29kd09")
+        });
+
+        let started = Instant::now();
+
+        assert!(matches!(
+            loopback.fetch_token(),
+            Err(LoopbackError::Token(TokenFetchError::AccessDenied))
+        ));
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(browser.join().unwrap().starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn times_out() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let auth = client
+            .authorization()
+            .redirect_uri(format!("http://localhost:{}/callback", free_port()))
+            .build();
+
+        assert!(matches!(
+            auth.loopback()
+                .unwrap()
+                .timeout(Duration::from_millis(50))
+                .fetch_token(),
+            Err(LoopbackError::TimedOut)
+        ));
+    }
+
+    #[test]
+    fn requires_loopback_redirect_uri() {
+        let client = Client::public("5fe01282e44241328a84e7c5cc169165", ScopeList::empty());
+
+        let auth = client
+            .authorization()
+            .redirect_uri("https://example.com/callback")
+            .build();
+
+        assert!(matches!(auth.loopback(), Err(LoopbackError::NotLoopback)));
+
+        let auth = client
+            .authorization()
+            .redirect_uri("http://127.0.0.1/callback")
+            .build();
+
+        assert!(matches!(auth.loopback(), Err(LoopbackError::NotLoopback)));
+    }
+}
@@ -1,10 +1,23 @@
-use serde::{Deserialize, Serialize};
+mod album;
+mod artist;
+mod audiobook;
+mod history;
+mod library;
+mod object;
+mod player;
+mod show;
+mod token;
+mod track;
+mod user;
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Token {
-    pub access_token: String,
-    pub token_type: String,
-    pub scope: String,
-    pub expires_in: i64,
-    pub refresh_token: Option<String>,
-}
+pub use album::*;
+pub use artist::*;
+pub use audiobook::*;
+pub use history::*;
+pub use library::*;
+pub use object::*;
+pub use player::*;
+pub use show::*;
+pub use token::*;
+pub use track::*;
+pub use user::*;
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Copyright, ExternalIds, ExternalUrls, Image, Paging, Restrictions, SimplifiedArtist,
+    SimplifiedTrack,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AlbumType {
+    Album,
+    Single,
+    Compilation,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DatePrecision {
+    Year,
+    Month,
+    Day,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SimplifiedAlbum {
+    pub album_type: Option<AlbumType>,
+    pub artists: Vec<SimplifiedArtist>,
+    #[serde(default)]
+    pub available_markets: Vec<String>,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: Option<String>,
+    pub id: Option<String>,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    pub name: String,
+    pub release_date: Option<String>,
+    pub release_date_precision: Option<DatePrecision>,
+    pub restrictions: Option<Restrictions>,
+    pub total_tracks: Option<u32>,
+    pub uri: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Album {
+    pub album_type: AlbumType,
+    pub artists: Vec<SimplifiedArtist>,
+    #[serde(default)]
+    pub available_markets: Vec<String>,
+    #[serde(default)]
+    pub copyrights: Vec<Copyright>,
+    #[serde(default)]
+    pub external_ids: ExternalIds,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    pub href: String,
+    pub id: String,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    pub label: Option<String>,
+    pub name: String,
+    pub popularity: Option<u32>,
+    pub release_date: String,
+    pub release_date_precision: DatePrecision,
+    pub restrictions: Option<Restrictions>,
+    pub total_tracks: u32,
+    pub tracks: Paging<SimplifiedTrack>,
+    pub uri: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{CopyrightType, RestrictionReason};
+
+    #[test]
+    fn album() {
+        let album: Album = serde_json::from_str(include_str!("fixtures/album.json")).unwrap();
+
+        assert_eq!(album.album_type, AlbumType::Compilation);
+        assert_eq!(album.name, "Brainfeeder Presents: 10 Years");
+        assert_eq!(album.release_date_precision, DatePrecision::Day);
+        assert_eq!(album.external_ids.upc.as_deref(), Some("5054429132328"));
+        assert_eq!(album.copyrights[1].kind, CopyrightType::P);
+        assert_eq!(album.label.as_deref(), Some("Brainfeeder"));
+        assert_eq!(
+            album.restrictions.unwrap().reason,
+            RestrictionReason::Market
+        );
+
+        assert_eq!(album.tracks.total, 2);
+        assert!(album.tracks.next.is_none());
+
+        let track = &album.tracks.items[1];
+        assert_eq!(track.name, "Ain't No Sunshine");
+        assert_eq!(
+            track.linked_from.as_ref().unwrap().id,
+            "6kLCHFM39wkFjOuyPGLGeQ"
+        );
+    }
+
+    #[test]
+    fn simplified_album() {
+        let album: SimplifiedAlbum = serde_json::from_str(
+            r#"{"album_type":"single","artists":[{"name":"Daft Punk"}],"images":[],"name":"One More Time"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(album.album_type, Some(AlbumType::Single));
+        assert!(album.id.is_none());
+        assert!(album.release_date.is_none());
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ExternalUrls, Followers, Image};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SimplifiedArtist {
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: Option<String>,
+    pub id: Option<String>,
+    pub name: String,
+    pub uri: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Artist {
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    #[serde(default)]
+    pub followers: Followers,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    pub href: String,
+    pub id: String,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    pub name: String,
+    pub popularity: u32,
+    pub uri: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn artist() {
+        let artist: Artist = serde_json::from_str(include_str!("fixtures/artist.json")).unwrap();
+
+        assert_eq!(artist.name, "Pitbull");
+        assert_eq!(artist.followers.total, 7_800_448);
+        assert_eq!(
+            artist.genres,
+            ["dance pop", "latin", "miami hip hop", "pop"]
+        );
+        assert_eq!(artist.images.len(), 2);
+        assert_eq!(
+            artist.external_urls.spotify.as_deref(),
+            Some("https://open.spotify.com/artist/0TnOYISbd1XYRBk9myaseg")
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ExternalUrls, Image};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Narrator {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SimplifiedAudiobook {
+    #[serde(default)]
+    pub authors: Vec<Author>,
+    #[serde(default)]
+    pub available_markets: Vec<String>,
+    pub description: String,
+    pub edition: Option<String>,
+    pub explicit: bool,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: String,
+    pub id: String,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    pub media_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub narrators: Vec<Narrator>,
+    pub publisher: String,
+    pub total_chapters: u32,
+    pub uri: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn audiobook() {
+        let audiobook: SimplifiedAudiobook =
+            serde_json::from_str(include_str!("fixtures/audiobook.json")).unwrap();
+
+        assert_eq!(audiobook.name, "Dune");
+        assert_eq!(audiobook.authors[0].name, "Frank Herbert");
+        assert_eq!(audiobook.narrators.len(), 2);
+        assert_eq!(audiobook.total_chapters, 51);
+    }
+}
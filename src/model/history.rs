@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Context, Track};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlayHistory {
+    pub track: Track,
+    /// When the track was played, as an ISO 8601 timestamp in UTC.
+    pub played_at: String,
+    pub context: Option<Context>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::CursorPaging;
+
+    #[test]
+    fn recently_played() {
+        let page: CursorPaging<PlayHistory> =
+            serde_json::from_str(include_str!("fixtures/recently_played.json")).unwrap();
+
+        assert_eq!(page.limit, 2);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].played_at, "2020-01-19T12:22:05.385Z");
+        assert_eq!(page.items[0].track.name, "Cut To The Feeling");
+        assert!(page.items[1].context.is_none());
+        assert_eq!(
+            page.cursors.unwrap().before.as_deref(),
+            Some("1579436288032")
+        );
+        assert_eq!(
+            page.next.as_deref(),
+            Some(
+                "https://api.spotify.com/v1/me/player/recently-played?before=1579436288032&limit=2"
+            )
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Album, Episode, SimplifiedShow, Track};
+
+/// A track in the user's library.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SavedTrack {
+    /// When the track was saved, as an ISO 8601 timestamp in UTC.
+    pub added_at: String,
+    pub track: Track,
+}
+
+/// An album in the user's library.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SavedAlbum {
+    /// When the album was saved, as an ISO 8601 timestamp in UTC.
+    pub added_at: String,
+    pub album: Album,
+}
+
+/// A show in the user's library.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SavedShow {
+    /// When the show was saved, as an ISO 8601 timestamp in UTC.
+    pub added_at: String,
+    pub show: SimplifiedShow,
+}
+
+/// An episode in the user's library.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SavedEpisode {
+    /// When the episode was saved, as an ISO 8601 timestamp in UTC.
+    pub added_at: String,
+    pub episode: Episode,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn saved_track() {
+        let track: serde_json::Value =
+            serde_json::from_str(include_str!("fixtures/track.json")).unwrap();
+
+        let saved: SavedTrack = serde_json::from_value(serde_json::json!({
+            "added_at": "2016-10-24T15:03:07Z",
+            "track": track,
+        }))
+        .unwrap();
+
+        assert_eq!(saved.added_at, "2016-10-24T15:03:07Z");
+        assert!(!saved.track.name.is_empty());
+    }
+
+    #[test]
+    fn saved_album() {
+        let album: serde_json::Value =
+            serde_json::from_str(include_str!("fixtures/album.json")).unwrap();
+
+        let saved: SavedAlbum = serde_json::from_value(serde_json::json!({
+            "added_at": "2020-01-19T12:22:05Z",
+            "album": album,
+        }))
+        .unwrap();
+
+        assert_eq!(saved.added_at, "2020-01-19T12:22:05Z");
+        assert!(!saved.album.tracks.items.is_empty());
+    }
+
+    #[test]
+    fn saved_show() {
+        let show: serde_json::Value =
+            serde_json::from_str(include_str!("fixtures/show.json")).unwrap();
+
+        let saved: SavedShow = serde_json::from_value(serde_json::json!({
+            "added_at": "2020-07-31T09:44:21Z",
+            "show": show,
+        }))
+        .unwrap();
+
+        assert_eq!(saved.added_at, "2020-07-31T09:44:21Z");
+        assert_eq!(saved.show.name, "Vetenskapsradion Historia");
+    }
+
+    #[test]
+    fn saved_episode() {
+        let episode: serde_json::Value =
+            serde_json::from_str(include_str!("fixtures/episode.json")).unwrap();
+
+        let saved: SavedEpisode = serde_json::from_value(serde_json::json!({
+            "added_at": "2021-02-14T18:02:37Z",
+            "episode": episode,
+        }))
+        .unwrap();
+
+        assert_eq!(saved.added_at, "2021-02-14T18:02:37Z");
+        assert_eq!(saved.episode.show.unwrap().id, "38bS44xjbVVZ3No3ByF1dJ");
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Image {
+    pub url: String,
+    pub height: Option<u32>,
+    pub width: Option<u32>,
+}
+
+/// Known external urls of an object, keyed by type such as `spotify`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ExternalUrls {
+    pub spotify: Option<String>,
+    #[serde(flatten)]
+    pub other: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExternalIds {
+    pub isrc: Option<String>,
+    pub ean: Option<String>,
+    pub upc: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CopyrightType {
+    /// The copyright.
+    C,
+    /// The sound recording (performance) copyright.
+    P,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Copyright {
+    pub text: String,
+    #[serde(rename = "type")]
+    pub kind: CopyrightType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RestrictionReason {
+    Market,
+    Product,
+    Explicit,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Present on content that cannot be played, with the reason why.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Restrictions {
+    pub reason: RestrictionReason,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Followers {
+    pub href: Option<String>,
+    pub total: u64,
+}
+
+/// A page of a list of objects, the next and previous pages are found at the `next` and
+/// `previous` urls.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Paging<T> {
+    pub href: String,
+    pub items: Vec<T>,
+    pub limit: u32,
+    pub next: Option<String>,
+    pub offset: u32,
+    pub previous: Option<String>,
+    pub total: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cursors {
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+/// A page of a list of objects that is paged by cursors instead of offsets, the next page is
+/// found at the `next` url.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CursorPaging<T> {
+    pub href: String,
+    pub items: Vec<T>,
+    pub limit: u32,
+    pub next: Option<String>,
+    pub cursors: Option<Cursors>,
+    pub total: Option<u32>,
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Episode, ExternalUrls, Track};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceType {
+    Computer,
+    Tablet,
+    Smartphone,
+    Speaker,
+    #[serde(rename = "TV")]
+    Tv,
+    #[serde(rename = "AVR")]
+    Avr,
+    #[serde(rename = "STB")]
+    Stb,
+    AudioDongle,
+    GameConsole,
+    CastVideo,
+    CastAudio,
+    Automobile,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Device {
+    pub id: Option<String>,
+    pub is_active: bool,
+    pub is_private_session: bool,
+    pub is_restricted: bool,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: DeviceType,
+    pub volume_percent: Option<u8>,
+    #[serde(default)]
+    pub supports_volume: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RepeatState {
+    Off,
+    Track,
+    Context,
+}
+
+impl RepeatState {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RepeatState::Off => "off",
+            RepeatState::Track => "track",
+            RepeatState::Context => "context",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextType {
+    Album,
+    Artist,
+    Playlist,
+    Show,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Context {
+    #[serde(rename = "type")]
+    pub kind: ContextType,
+    pub href: Option<String>,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub uri: String,
+}
+
+/// Which playback actions are currently not allowed, missing entries are allowed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Disallows {
+    pub interrupting_playback: bool,
+    pub pausing: bool,
+    pub resuming: bool,
+    pub seeking: bool,
+    pub skipping_next: bool,
+    pub skipping_prev: bool,
+    pub toggling_repeat_context: bool,
+    pub toggling_shuffle: bool,
+    pub toggling_repeat_track: bool,
+    pub transferring_playback: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Actions {
+    #[serde(default)]
+    pub disallows: Disallows,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrentlyPlayingType {
+    Track,
+    Episode,
+    Ad,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The track or episode being played.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlayingItem {
+    Track(Track),
+    Episode(Episode),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CurrentlyPlaying {
+    pub context: Option<Context>,
+    pub timestamp: u64,
+    pub progress_ms: Option<u64>,
+    pub is_playing: bool,
+    pub item: Option<PlayingItem>,
+    pub currently_playing_type: CurrentlyPlayingType,
+    #[serde(default)]
+    pub actions: Actions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlaybackState {
+    pub device: Device,
+    pub repeat_state: RepeatState,
+    pub shuffle_state: bool,
+    pub context: Option<Context>,
+    pub timestamp: u64,
+    pub progress_ms: Option<u64>,
+    pub is_playing: bool,
+    pub item: Option<PlayingItem>,
+    pub currently_playing_type: CurrentlyPlayingType,
+    #[serde(default)]
+    pub actions: Actions,
+}
+
+/// The user's queue, upcoming items in order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Queue {
+    pub currently_playing: Option<PlayingItem>,
+    pub queue: Vec<PlayingItem>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn currently_playing_track() {
+        let playing: CurrentlyPlaying =
+            serde_json::from_str(include_str!("fixtures/currently_playing.json")).unwrap();
+
+        assert!(playing.is_playing);
+        assert_eq!(playing.progress_ms, Some(44272));
+        assert_eq!(playing.currently_playing_type, CurrentlyPlayingType::Track);
+        assert_eq!(playing.context.unwrap().kind, ContextType::Playlist);
+        assert!(playing.actions.disallows.resuming);
+        assert!(!playing.actions.disallows.pausing);
+
+        match playing.item {
+            Some(PlayingItem::Track(track)) => {
+                assert_eq!(track.name, "Cut To The Feeling");
+                assert_eq!(track.artists[0].name, "Carly Rae Jepsen");
+                assert_eq!(track.album.images[1].width, Some(300));
+            }
+            _ => panic!("expected a track"),
+        }
+    }
+
+    #[test]
+    fn playback_state_episode() {
+        let state: PlaybackState =
+            serde_json::from_str(include_str!("fixtures/playback_state.json")).unwrap();
+
+        assert_eq!(state.device.kind, DeviceType::Smartphone);
+        assert_eq!(state.device.volume_percent, Some(54));
+        assert_eq!(state.repeat_state, RepeatState::Context);
+        assert!(!state.shuffle_state);
+        assert_eq!(state.currently_playing_type, CurrentlyPlayingType::Episode);
+
+        match state.item {
+            Some(PlayingItem::Episode(episode)) => {
+                assert_eq!(episode.name, "Tredje rikets knarkande granskas");
+                assert_eq!(episode.show.unwrap().publisher, "Sveriges Radio");
+            }
+            _ => panic!("expected an episode"),
+        }
+    }
+
+    #[test]
+    fn unknown_device_type() {
+        let device: Device = serde_json::from_str(
+            r#"{"id":null,"is_active":false,"is_private_session":false,"is_restricted":true,"name":"Car","type":"Hoverboard","volume_percent":null}"#,
+        )
+        .unwrap();
+
+        assert_eq!(device.kind, DeviceType::Unknown);
+        assert!(!device.supports_volume);
+    }
+
+    #[test]
+    fn queue() {
+        let playing: CurrentlyPlaying =
+            serde_json::from_str(include_str!("fixtures/currently_playing.json")).unwrap();
+        let state: PlaybackState =
+            serde_json::from_str(include_str!("fixtures/playback_state.json")).unwrap();
+
+        let json = serde_json::json!({
+            "currently_playing": playing.item,
+            "queue": [state.item],
+        });
+
+        let queue: Queue = serde_json::from_value(json).unwrap();
+
+        assert!(matches!(
+            queue.currently_playing,
+            Some(PlayingItem::Track(_))
+        ));
+        assert_eq!(queue.queue.len(), 1);
+        assert!(matches!(queue.queue[0], PlayingItem::Episode(_)));
+
+        let queue: Queue =
+            serde_json::from_str(r#"{"currently_playing":null,"queue":[]}"#).unwrap();
+
+        assert!(queue.currently_playing.is_none());
+        assert!(queue.queue.is_empty());
+    }
+}
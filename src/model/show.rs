@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DatePrecision, ExternalUrls, Image};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SimplifiedShow {
+    #[serde(default)]
+    pub available_markets: Vec<String>,
+    pub description: String,
+    pub explicit: bool,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: String,
+    pub id: String,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    pub is_externally_hosted: Option<bool>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    pub media_type: String,
+    pub name: String,
+    pub publisher: String,
+    pub uri: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Episode {
+    pub audio_preview_url: Option<String>,
+    pub description: String,
+    pub duration_ms: u64,
+    pub explicit: bool,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: String,
+    pub id: String,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    pub is_externally_hosted: Option<bool>,
+    pub is_playable: Option<bool>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    pub name: String,
+    pub release_date: String,
+    pub release_date_precision: DatePrecision,
+    pub show: Option<SimplifiedShow>,
+    pub uri: String,
+}
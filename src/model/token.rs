@@ -0,0 +1,135 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Token {
+    pub access_token: String,
+    pub token_type: String,
+    #[serde(default)]
+    pub scope: String,
+    pub expires_in: i64,
+    pub refresh_token: Option<String>,
+    /// When the token was obtained, `expires_in` is relative to this instant. Taken to be the
+    /// time of deserialization when absent, as it is in responses from the accounts service.
+    #[serde(default = "SystemTime::now", with = "unix_seconds")]
+    pub obtained_at: SystemTime,
+}
+
+impl Token {
+    /// When the token expires, or `None` if that is too far in the future to represent, in
+    /// which case the token never expires.
+    #[must_use]
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.obtained_at
+            .checked_add(Duration::from_secs(self.expires_in.max(0) as u64))
+    }
+
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(Duration::from_secs(0))
+    }
+
+    /// Whether the token is expired or will expire within `duration` from now.
+    #[must_use]
+    pub fn expires_within(&self, duration: Duration) -> bool {
+        match (SystemTime::now().checked_add(duration), self.expires_at()) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(then), Some(expires_at)) => then >= expires_at,
+        }
+    }
+}
+
+mod unix_seconds {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        serializer.serialize_u64(seconds)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = u64::deserialize(deserializer)?;
+
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(seconds))
+            .ok_or_else(|| D::Error::custom(format!("{} seconds is out of range", seconds)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::Token;
+
+    #[test]
+    fn token_from_response_obtained_now() {
+        let before = SystemTime::now() - Duration::from_secs(1);
+
+        let token: Token = serde_json::from_str(
+            r#"{"access_token":"NgCXRK","token_type":"Bearer","scope":"user-read-email","expires_in":3600,"refresh_token":"NgAagA"}"#,
+        )
+        .unwrap();
+
+        assert!(token.obtained_at >= before);
+        assert!(!token.is_expired());
+        assert!(!token.expires_within(Duration::from_secs(3000)));
+        assert!(token.expires_within(Duration::from_secs(3601)));
+        assert!(token.expires_within(Duration::from_secs(u64::MAX)));
+    }
+
+    #[test]
+    fn token_round_trips_expiry() {
+        let token: Token = serde_json::from_str(
+            r#"{"access_token":"NgCXRK","token_type":"Bearer","scope":"","expires_in":3600,"refresh_token":null,"obtained_at":1577836800}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            token.expires_at(),
+            Some(UNIX_EPOCH + Duration::from_secs(1577836800 + 3600))
+        );
+        assert!(token.is_expired());
+
+        let persisted: Token =
+            serde_json::from_str(&serde_json::to_string(&token).unwrap()).unwrap();
+
+        assert_eq!(persisted.obtained_at, token.obtained_at);
+        assert_eq!(persisted.expires_at(), token.expires_at());
+    }
+
+    #[test]
+    fn obtained_at_out_of_range() {
+        assert!(serde_json::from_str::<Token>(
+            r#"{"access_token":"a","token_type":"Bearer","expires_in":3600,"refresh_token":null,"obtained_at":18446744073709551615}"#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn expiry_out_of_range_never_expires() {
+        let token: Token = serde_json::from_str(
+            r#"{"access_token":"a","token_type":"Bearer","expires_in":9223372036854775807,"refresh_token":null,"obtained_at":1577836800}"#,
+        )
+        .unwrap();
+
+        assert_eq!(token.expires_at(), None);
+        assert!(!token.is_expired());
+        assert!(!token.expires_within(Duration::from_secs(u64::MAX)));
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ExternalIds, ExternalUrls, Restrictions, SimplifiedAlbum, SimplifiedArtist};
+
+/// The track originally requested, when [track relinking] replaced it with another.
+///
+/// [track relinking]: https://developer.spotify.com/documentation/general/guides/track-relinking-guide/
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LinkedFrom {
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: String,
+    pub id: String,
+    pub uri: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Track {
+    pub album: SimplifiedAlbum,
+    pub artists: Vec<SimplifiedArtist>,
+    #[serde(default)]
+    pub available_markets: Vec<String>,
+    pub disc_number: u32,
+    pub duration_ms: u64,
+    pub explicit: bool,
+    #[serde(default)]
+    pub external_ids: ExternalIds,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: Option<String>,
+    pub id: Option<String>,
+    pub is_playable: Option<bool>,
+    pub linked_from: Option<LinkedFrom>,
+    pub restrictions: Option<Restrictions>,
+    #[serde(default)]
+    pub is_local: bool,
+    pub name: String,
+    pub popularity: u32,
+    pub preview_url: Option<String>,
+    pub track_number: u32,
+    pub uri: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SimplifiedTrack {
+    pub artists: Vec<SimplifiedArtist>,
+    #[serde(default)]
+    pub available_markets: Vec<String>,
+    pub disc_number: u32,
+    pub duration_ms: u64,
+    pub explicit: bool,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    pub href: Option<String>,
+    pub id: Option<String>,
+    pub is_playable: Option<bool>,
+    pub linked_from: Option<LinkedFrom>,
+    pub restrictions: Option<Restrictions>,
+    #[serde(default)]
+    pub is_local: bool,
+    pub name: String,
+    pub preview_url: Option<String>,
+    pub track_number: u32,
+    pub uri: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{AlbumType, RestrictionReason};
+
+    #[test]
+    fn track() {
+        let track: Track = serde_json::from_str(include_str!("fixtures/track.json")).unwrap();
+
+        assert_eq!(track.name, "Cut To The Feeling");
+        assert_eq!(track.external_ids.isrc.as_deref(), Some("USUM71703861"));
+        assert_eq!(track.album.album_type, Some(AlbumType::Single));
+        assert_eq!(track.is_playable, Some(false));
+        assert_eq!(
+            track.restrictions.unwrap().reason,
+            RestrictionReason::Market
+        );
+        assert!(track.available_markets.is_empty());
+        assert!(track.linked_from.is_none());
+    }
+
+    #[test]
+    fn local_track() {
+        let track: Track = serde_json::from_str(include_str!("fixtures/local_track.json")).unwrap();
+
+        assert!(track.is_local);
+        assert!(track.id.is_none());
+        assert!(track.album.id.is_none());
+        assert_eq!(track.artists[0].name, "Unknown Artist");
+    }
+}
@@ -0,0 +1,123 @@
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ExternalUrls, Followers, Image};
+use crate::scope::{Scoped, UserReadEmail, UserReadPrivate};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Product {
+    Premium,
+    Free,
+    Open,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExplicitContent {
+    /// Explicit content should not be played.
+    pub filter_enabled: bool,
+    /// The setting is locked and cannot be changed by the user.
+    pub filter_locked: bool,
+}
+
+/// The profile of the current user. Fields that Spotify only returns when the token was granted
+/// a scope are accessible only when `Scopes` includes that scope.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrivateUser<Scopes> {
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    #[serde(default)]
+    pub followers: Followers,
+    pub href: String,
+    pub id: String,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    pub uri: String,
+    email: Option<String>,
+    country: Option<String>,
+    product: Option<Product>,
+    explicit_content: Option<ExplicitContent>,
+    #[serde(skip)]
+    _scopes: PhantomData<Scopes>,
+}
+
+impl<Scopes> PrivateUser<Scopes>
+where
+    Scopes: Scoped<UserReadEmail>,
+{
+    /// The email address entered by the user, which Spotify does not verify.
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+}
+
+impl<Scopes> PrivateUser<Scopes>
+where
+    Scopes: Scoped<UserReadPrivate>,
+{
+    /// The country of the user as an ISO 3166-1 alpha-2 code.
+    pub fn country(&self) -> Option<&str> {
+        self.country.as_deref()
+    }
+
+    pub fn product(&self) -> Option<Product> {
+        self.product
+    }
+
+    pub fn explicit_content(&self) -> Option<ExplicitContent> {
+        self.explicit_content
+    }
+}
+
+/// The public profile of any user.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PublicUser {
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub external_urls: ExternalUrls,
+    #[serde(default)]
+    pub followers: Followers,
+    pub href: String,
+    pub id: String,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    pub uri: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scopes;
+
+    #[test]
+    fn private_user() {
+        let user: PrivateUser<scopes![UserReadEmail, UserReadPrivate]> =
+            serde_json::from_str(include_str!("fixtures/private_user.json")).unwrap();
+
+        assert_eq!(user.id, "wizzler");
+        assert_eq!(user.display_name.as_deref(), Some("JM Wizzler"));
+        assert_eq!(user.followers.total, 3829);
+        assert_eq!(user.email(), Some("email@example.com"));
+        assert_eq!(user.country(), Some("SE"));
+        assert_eq!(user.product(), Some(Product::Premium));
+        assert!(!user.explicit_content().unwrap().filter_enabled);
+    }
+
+    #[test]
+    fn public_user() {
+        let user: PublicUser =
+            serde_json::from_str(include_str!("fixtures/private_user.json")).unwrap();
+
+        assert_eq!(user.id, "wizzler");
+        assert_eq!(user.followers.total, 3829);
+        assert_eq!(
+            user.external_urls.spotify.as_deref(),
+            Some("https://open.spotify.com/user/wizzler")
+        );
+        assert!(user.images[0].width.is_none());
+    }
+}
@@ -0,0 +1,281 @@
+use std::ops::Deref;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::ApiError;
+use crate::model::{CursorPaging, Paging};
+use crate::Spotify;
+
+/// A page returned by a [`Spotify`] handle, which can lazily fetch the pages after it with the
+/// same handle. Derefs to the [`Paging`] object itself.
+pub struct Page<'s, Scopes, T> {
+    spotify: &'s Spotify<Scopes>,
+    page: Paging<T>,
+}
+
+impl<'s, Scopes, T> Page<'s, Scopes, T> {
+    pub(crate) fn new(spotify: &'s Spotify<Scopes>, page: Paging<T>) -> Self {
+        Self { spotify, page }
+    }
+
+    pub fn into_inner(self) -> Paging<T> {
+        self.page
+    }
+
+    /// Iterates over the items of this page and of every page after it, fetching the next page
+    /// only once the items before it are consumed.
+    pub fn all(self) -> PagingIter<'s, Scopes, T> {
+        PagingIter {
+            spotify: self.spotify,
+            items: self.page.items.into_iter(),
+            next: self.page.next,
+        }
+    }
+}
+
+impl<Scopes, T> Deref for Page<'_, Scopes, T> {
+    type Target = Paging<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.page
+    }
+}
+
+/// Iterator returned by [`Page::all`]. Stops after the first error.
+pub struct PagingIter<'s, Scopes, T> {
+    spotify: &'s Spotify<Scopes>,
+    items: std::vec::IntoIter<T>,
+    next: Option<String>,
+}
+
+impl<Scopes, T> Iterator for PagingIter<'_, Scopes, T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T, ApiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            let next = self.next.take()?;
+
+            match self.spotify.get::<Paging<T>>(&next) {
+                Ok(page) => {
+                    self.items = page.items.into_iter();
+                    self.next = page.next;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+type FetchCursorPage<'s, Scopes, T> =
+    fn(&'s Spotify<Scopes>, &str) -> Result<CursorPaging<T>, ApiError>;
+
+/// A cursor paged page returned by a [`Spotify`] handle, which can lazily fetch the pages after
+/// it with the same handle. Derefs to the [`CursorPaging`] object itself.
+pub struct CursorPage<'s, Scopes, T> {
+    spotify: &'s Spotify<Scopes>,
+    page: CursorPaging<T>,
+    fetch: FetchCursorPage<'s, Scopes, T>,
+}
+
+impl<'s, Scopes, T> CursorPage<'s, Scopes, T>
+where
+    T: DeserializeOwned,
+{
+    pub(crate) fn new(spotify: &'s Spotify<Scopes>, page: CursorPaging<T>) -> Self {
+        Self::with_fetch(spotify, page, |spotify, url| spotify.get(url))
+    }
+
+    /// Like [`new`](Self::new), for endpoints that wrap their pages in another object. `fetch`
+    /// fetches and unwraps the page at a `next` url.
+    pub(crate) fn with_fetch(
+        spotify: &'s Spotify<Scopes>,
+        page: CursorPaging<T>,
+        fetch: FetchCursorPage<'s, Scopes, T>,
+    ) -> Self {
+        Self {
+            spotify,
+            page,
+            fetch,
+        }
+    }
+}
+
+impl<'s, Scopes, T> CursorPage<'s, Scopes, T> {
+    pub fn into_inner(self) -> CursorPaging<T> {
+        self.page
+    }
+
+    /// Iterates over the items of this page and of every page after it, fetching the next page
+    /// only once the items before it are consumed.
+    pub fn all(self) -> CursorPagingIter<'s, Scopes, T> {
+        CursorPagingIter {
+            spotify: self.spotify,
+            items: self.page.items.into_iter(),
+            next: self.page.next,
+            fetch: self.fetch,
+        }
+    }
+}
+
+impl<Scopes, T> Deref for CursorPage<'_, Scopes, T> {
+    type Target = CursorPaging<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.page
+    }
+}
+
+/// Iterator returned by [`CursorPage::all`]. Stops after the first error, or at the first empty
+/// page, as some endpoints keep returning a `next` url past the end.
+pub struct CursorPagingIter<'s, Scopes, T> {
+    spotify: &'s Spotify<Scopes>,
+    items: std::vec::IntoIter<T>,
+    next: Option<String>,
+    fetch: FetchCursorPage<'s, Scopes, T>,
+}
+
+impl<Scopes, T> Iterator for CursorPagingIter<'_, Scopes, T> {
+    type Item = Result<T, ApiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            let next = self.next.take()?;
+
+            match (self.fetch)(self.spotify, &next) {
+                Ok(page) => {
+                    if page.items.is_empty() {
+                        return None;
+                    }
+
+                    self.items = page.items.into_iter();
+                    self.next = page.next;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::Token;
+    use crate::scope::*;
+    use crate::{scopes, Client};
+
+    type Scope = scopes![UserReadRecentlyPlayed];
+
+    fn spotify() -> Spotify<Scope> {
+        let token = Token {
+            access_token: String::from("reojwgpoerjg"),
+            token_type: String::from("Bearer"),
+            scope: Scope::joined_names().unwrap(),
+            expires_in: 3600,
+            refresh_token: None,
+            obtained_at: std::time::SystemTime::now(),
+        };
+
+        let client = Client::new(
+            "5fe01282e44241328a84e7c5cc169165",
+            ";awoeifjigowerg",
+            Scope::create(),
+        );
+
+        client.with_access_token(&token).unwrap()
+    }
+
+    fn paging(items: Vec<u32>, next: Option<&str>) -> Paging<u32> {
+        Paging {
+            href: String::from("https://api.spotify.com/v1/me/tracks?offset=0&limit=2"),
+            total: 3,
+            limit: 2,
+            offset: 0,
+            items,
+            next: next.map(String::from),
+            previous: None,
+        }
+    }
+
+    #[test]
+    fn follows_next() {
+        let spotify = spotify();
+
+        let page = Page::new(&spotify, paging(vec![1, 2], None));
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            page.all().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![1, 2]
+        );
+
+        // The next page is only fetched once the first one is consumed, and fails with the made
+        // up token.
+        let mut all = Page::new(
+            &spotify,
+            paging(
+                vec![1, 2],
+                Some("https://api.spotify.com/v1/me/tracks?offset=2&limit=2"),
+            ),
+        )
+        .all();
+
+        assert_eq!(all.next().unwrap().unwrap(), 1);
+        assert_eq!(all.next().unwrap().unwrap(), 2);
+        assert!(all.next().unwrap().is_err());
+        assert!(all.next().is_none());
+    }
+
+    #[test]
+    fn cursor_page() {
+        let spotify = spotify();
+
+        let page = CursorPage::new(
+            &spotify,
+            CursorPaging {
+                href: String::from("https://api.spotify.com/v1/me/player/recently-played"),
+                items: vec![1, 2],
+                limit: 2,
+                next: None,
+                cursors: None,
+                total: None,
+            },
+        );
+
+        assert_eq!(page.limit, 2);
+        assert_eq!(
+            page.all().collect::<Result<Vec<u32>, _>>().unwrap(),
+            vec![1, 2]
+        );
+
+        // The next page is only fetched once the first one is consumed, and fails with the made
+        // up token.
+        let mut all = CursorPage::new(
+            &spotify,
+            CursorPaging {
+                href: String::from("https://api.spotify.com/v1/me/player/recently-played"),
+                items: vec![1],
+                limit: 1,
+                next: Some(String::from(
+                    "https://api.spotify.com/v1/me/player/recently-played?before=1579436288032&limit=1",
+                )),
+                cursors: None,
+                total: None,
+            },
+        )
+        .all();
+
+        assert_eq!(all.next().unwrap().unwrap(), 1);
+        assert!(all.next().unwrap().is_err());
+        assert!(all.next().is_none());
+    }
+}
@@ -176,6 +176,11 @@ impl ScopeListBehaviour for ScopeList<Nil> {
     }
 }
 
+/// The scopes of a handle with an app-only token from the Client Credentials flow, which no user
+/// granted any scopes.
+#[derive(Debug, Copy, Clone)]
+pub struct AppOnly;
+
 impl ScopeList<Nil> {
     pub const fn empty() -> ScopeList<Nil> {
         Self {
@@ -191,7 +196,7 @@ impl<C> ScopeList<C> {
         }
     }
 
-    #[allow(unused_variables)]
+    #[allow(unused_variables, clippy::should_implement_trait)]
     pub fn add<S>(self, scope: S) -> ScopeList<C::Result>
     where
         C: Prepend<S>,
@@ -0,0 +1,284 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use attohttpc::StatusCode;
+
+use crate::error::{ApiError, TokenFetchError, TokenStoreError};
+use crate::model::Token;
+use crate::store::TokenStore;
+use crate::Spotify;
+
+/// Tokens expiring within this margin are refreshed before sending a request, so that the token
+/// does not expire while the request is in flight.
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+type RefreshCallback = Box<dyn Fn(&Token) + Send + Sync>;
+type StoreErrorCallback = Box<dyn Fn(&TokenStoreError) + Send + Sync>;
+
+/// The token of a [`Spotify`] handle that refreshes itself, with what is needed to refresh it.
+pub(crate) struct Session {
+    client_id: String,
+    authorization_header: Option<String>,
+    token: Mutex<Token>,
+    on_refresh: Option<RefreshCallback>,
+    store: Option<(Arc<dyn TokenStore>, String)>,
+    on_store_error: Option<StoreErrorCallback>,
+    /// Whether the current token failed to save, and is saved again before the next request.
+    unsaved: AtomicBool,
+}
+
+impl Session {
+    pub(crate) fn new(client_id: &str, authorization_header: Option<String>, token: Token) -> Self {
+        Session {
+            client_id: client_id.to_owned(),
+            authorization_header,
+            token: Mutex::new(token),
+            on_refresh: None,
+            store: None,
+            on_store_error: None,
+            unsaved: AtomicBool::new(false),
+        }
+    }
+
+    /// Refreshes the token if `needed` returns true for the current token and bearer header,
+    /// and stores the new bearer header in `bearer`. Tokens without a refresh token are left as is.
+    fn refresh_if(
+        &self,
+        bearer: &RwLock<String>,
+        needed: impl FnOnce(&Token, &str) -> bool,
+    ) -> Result<(), TokenFetchError> {
+        let mut token = self.token.lock().unwrap();
+
+        if !needed(&token, &bearer.read().unwrap()) {
+            return Ok(());
+        }
+
+        let refreshed = crate::authorization::refresh_token(
+            self.authorization_header.as_deref(),
+            &self.client_id,
+            &token,
+        );
+
+        *token = match refreshed {
+            Ok(refreshed) => refreshed,
+            Err(TokenFetchError::NoRefreshToken) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        *bearer.write().unwrap() = crate::bearer(&token);
+        self.save(&token);
+
+        if let Some(on_refresh) = self.on_refresh.as_ref() {
+            on_refresh(&token);
+        }
+
+        Ok(())
+    }
+
+    /// Writes `token` to the token store, if any. A failure is reported to the store error
+    /// callback and leaves the session marked as unsaved.
+    fn save(&self, token: &Token) {
+        let (store, key) = match self.store.as_ref() {
+            Some(store) => store,
+            None => return,
+        };
+
+        match store.save(key, token) {
+            Ok(()) => self.unsaved.store(false, Ordering::SeqCst),
+            Err(e) => {
+                self.unsaved.store(true, Ordering::SeqCst);
+
+                if let Some(on_store_error) = self.on_store_error.as_ref() {
+                    on_store_error(&e);
+                }
+            }
+        }
+    }
+
+    /// Saves the current token again if it failed to save before.
+    fn save_if_unsaved(&self) {
+        if self.unsaved.load(Ordering::SeqCst) {
+            let token = self.token.lock().unwrap();
+            self.save(&token);
+        }
+    }
+}
+
+impl<Scopes> Spotify<Scopes> {
+    /// Registers a callback invoked with every token the handle refreshes, so it can be
+    /// persisted. Has no effect on handles not created with
+    /// [`Client::with_refreshing_token`](crate::Client::with_refreshing_token).
+    #[must_use]
+    pub fn on_token_refresh(mut self, callback: impl Fn(&Token) + Send + Sync + 'static) -> Self {
+        if let Some(session) = self.session.as_mut() {
+            session.on_refresh = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    /// Writes every token the handle refreshes to `store` under `key`. Has no effect on handles
+    /// not created with [`Client::with_refreshing_token`](crate::Client::with_refreshing_token).
+    ///
+    /// A token that could not be saved stays in use, and saving it is retried before every
+    /// request until it succeeds. Failures are reported to
+    /// [`on_token_store_error`](Self::on_token_store_error).
+    #[must_use]
+    pub fn token_store(mut self, store: Arc<dyn TokenStore>, key: impl Into<String>) -> Self {
+        if let Some(session) = self.session.as_mut() {
+            session.store = Some((store, key.into()));
+        }
+
+        self
+    }
+
+    /// Registers a callback invoked every time a token could not be written to the
+    /// [`token_store`](Self::token_store). Requests are sent regardless.
+    #[must_use]
+    pub fn on_token_store_error(
+        mut self,
+        callback: impl Fn(&TokenStoreError) + Send + Sync + 'static,
+    ) -> Self {
+        if let Some(session) = self.session.as_mut() {
+            session.on_store_error = Some(Box::new(callback));
+        }
+
+        self
+    }
+
+    /// The current token of a handle created with
+    /// [`Client::with_refreshing_token`](crate::Client::with_refreshing_token).
+    pub fn token(&self) -> Option<Token> {
+        self.session
+            .as_ref()
+            .map(|session| session.token.lock().unwrap().clone())
+    }
+
+    /// Sends the request built by `request` with the current bearer header. If the handle owns
+    /// its token, it is refreshed before sending when about to expire, and the request is sent
+    /// once more with a refreshed token if it was rejected as unauthorized.
+    ///
+    /// Error responses are turned into [`ApiError`]s.
+    pub(crate) fn send(
+        &self,
+        request: impl Fn(&str) -> attohttpc::Result<attohttpc::Response>,
+    ) -> Result<attohttpc::Response, ApiError> {
+        crate::error::error_for_status(self.send_authorized(request)?)
+    }
+
+    fn send_authorized(
+        &self,
+        request: impl Fn(&str) -> attohttpc::Result<attohttpc::Response>,
+    ) -> Result<attohttpc::Response, ApiError> {
+        let session = match self.session.as_ref() {
+            Some(session) => session,
+            None => return Ok(request(&self.authorization_header.read().unwrap())?),
+        };
+
+        session.save_if_unsaved();
+        session.refresh_if(&self.authorization_header, |token, _| {
+            token.expires_within(REFRESH_MARGIN)
+        })?;
+
+        let sent_with = self.authorization_header.read().unwrap().clone();
+        let response = request(&sent_with)?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        // Another request may have refreshed the token since this one was sent.
+        session.refresh_if(&self.authorization_header, |_, current| {
+            current == sent_with
+        })?;
+
+        let current = self.authorization_header.read().unwrap().clone();
+
+        if current == sent_with {
+            Ok(response)
+        } else {
+            Ok(request(&current)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::store::MemoryTokenStore;
+
+    /// Fails to save while `failing` is set, and saves to memory otherwise.
+    #[derive(Default)]
+    struct FlakyStore {
+        failing: AtomicBool,
+        tokens: MemoryTokenStore,
+    }
+
+    impl TokenStore for FlakyStore {
+        fn load(&self, key: &str) -> Result<Option<Token>, TokenStoreError> {
+            self.tokens.load(key)
+        }
+
+        fn save(&self, key: &str, token: &Token) -> Result<(), TokenStoreError> {
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(io::Error::other("disk full").into());
+            }
+
+            self.tokens.save(key, token)
+        }
+
+        fn delete(&self, key: &str) -> Result<(), TokenStoreError> {
+            self.tokens.delete(key)
+        }
+    }
+
+    #[test]
+    fn retries_failed_saves() {
+        let store = Arc::new(FlakyStore::default());
+        store.failing.store(true, Ordering::SeqCst);
+        let errors = Arc::new(AtomicUsize::new(0));
+
+        let mut session = Session::new(
+            "client",
+            None,
+            Token {
+                access_token: String::from("wpoejfwpe"),
+                token_type: String::from("Bearer"),
+                scope: String::new(),
+                expires_in: 3600,
+                refresh_token: Some(String::from("ewfjiowef")),
+                obtained_at: SystemTime::now(),
+            },
+        );
+        session.store = Some((store.clone(), String::from("alice")));
+        let on_store_error = errors.clone();
+        session.on_store_error = Some(Box::new(move |_| {
+            on_store_error.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        session.save(&session.token.lock().unwrap().clone());
+        assert_eq!(errors.load(Ordering::SeqCst), 1);
+        assert!(store.load("alice").unwrap().is_none());
+
+        session.save_if_unsaved();
+        assert_eq!(errors.load(Ordering::SeqCst), 2);
+
+        store.failing.store(false, Ordering::SeqCst);
+        session.save_if_unsaved();
+        assert_eq!(errors.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            store.load("alice").unwrap().unwrap().access_token,
+            "wpoejfwpe"
+        );
+
+        // Saved tokens are not written again.
+        store.delete("alice").unwrap();
+        session.save_if_unsaved();
+        assert!(store.load("alice").unwrap().is_none());
+    }
+}
@@ -1,12 +1,18 @@
 use attohttpc::header::{AUTHORIZATION, CONTENT_LENGTH};
+use attohttpc::{Method, RequestBuilder};
 use derive_builder::Builder;
-use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_with_macros::skip_serializing_none;
+use url::Url;
 
+use crate::error::ApiError;
+use crate::model::{
+    Artist, CurrentlyPlaying, CursorPaging, Device, PlayHistory, PlaybackState, PrivateUser,
+    PublicUser, Queue, RepeatState, Track,
+};
 use crate::scope::*;
-use crate::Spotify;
-
-type Response = attohttpc::Result<attohttpc::Response>;
+use crate::{CursorPage, Page, Spotify};
 
 /*
 * Endpoints:
@@ -21,6 +27,68 @@ type Response = attohttpc::Result<attohttpc::Response>;
 * Transfer a User's Playback
 */
 
+/// Item types besides tracks that the client supports, items of other types are returned as
+/// `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdditionalType {
+    Track,
+    Episode,
+}
+
+impl AdditionalType {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdditionalType::Track => "track",
+            AdditionalType::Episode => "episode",
+        }
+    }
+
+    fn join(types: &[AdditionalType]) -> Option<String> {
+        if types.is_empty() {
+            None
+        } else {
+            Some(
+                types
+                    .iter()
+                    .map(|kind| kind.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+        }
+    }
+}
+
+/// Limits recently played tracks to those played before or after a unix timestamp in
+/// milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlayedCursor {
+    Before(u64),
+    After(u64),
+}
+
+/// The period over which top artists and tracks are calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeRange {
+    /// Approximately the last 4 weeks.
+    ShortTerm,
+    /// Approximately the last 6 months.
+    MediumTerm,
+    /// Several years of data, including all new data as it becomes available.
+    LongTerm,
+}
+
+impl TimeRange {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimeRange::ShortTerm => "short_term",
+            TimeRange::MediumTerm => "medium_term",
+            TimeRange::LongTerm => "long_term",
+        }
+    }
+}
+
 #[derive(Serialize)]
 #[serde(untagged)]
 pub enum Offset {
@@ -37,6 +105,23 @@ struct ResumePlaybackBody<'a> {
     position_ms: Option<u64>,
 }
 
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct TransferPlaybackBody<'a> {
+    device_ids: [&'a str; 1],
+    play: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct Devices {
+    devices: Vec<Device>,
+}
+
+#[derive(Deserialize)]
+struct FollowedArtists {
+    artists: CursorPaging<Artist>,
+}
+
 #[derive(Builder, Default)]
 #[builder(pattern = "owned")]
 #[builder(build_fn(skip))]
@@ -82,29 +167,176 @@ impl<Scopes> Spotify<Scopes>
 where
     Scopes: Scoped<UserModifyPlaybackState>,
 {
-    pub fn pause_playback<'a>(&self, device_id: impl Into<Option<&'a str>>) -> Response {
-        let mut req = attohttpc::put("https://api.spotify.com/v1/me/player/pause")
-            .header(AUTHORIZATION, &self.authorization_header)
-            .header(CONTENT_LENGTH, 0);
+    pub fn pause_playback<'a>(
+        &self,
+        device_id: impl Into<Option<&'a str>>,
+    ) -> Result<(), ApiError> {
+        self.player_command(
+            Method::PUT,
+            "https://api.spotify.com/v1/me/player/pause",
+            &[],
+            device_id.into(),
+        )
+    }
 
-        if let Some(device_id) = device_id.into() {
-            req = req.param("device_id", device_id);
+    pub fn seek<'a>(
+        &self,
+        position_ms: u64,
+        device_id: impl Into<Option<&'a str>>,
+    ) -> Result<(), ApiError> {
+        self.player_command(
+            Method::PUT,
+            "https://api.spotify.com/v1/me/player/seek",
+            &[("position_ms", &position_ms.to_string())],
+            device_id.into(),
+        )
+    }
+
+    pub fn set_repeat<'a>(
+        &self,
+        state: RepeatState,
+        device_id: impl Into<Option<&'a str>>,
+    ) -> Result<(), ApiError> {
+        self.player_command(
+            Method::PUT,
+            "https://api.spotify.com/v1/me/player/repeat",
+            &[("state", state.as_str())],
+            device_id.into(),
+        )
+    }
+
+    /// Sets the volume in percent, `volume_percent` must be between 0 and 100.
+    pub fn set_volume<'a>(
+        &self,
+        volume_percent: u8,
+        device_id: impl Into<Option<&'a str>>,
+    ) -> Result<(), ApiError> {
+        if volume_percent > 100 {
+            return Err(ApiError::InvalidVolume(volume_percent));
         }
 
-        req.send()
+        self.player_command(
+            Method::PUT,
+            "https://api.spotify.com/v1/me/player/volume",
+            &[("volume_percent", &volume_percent.to_string())],
+            device_id.into(),
+        )
+    }
+
+    pub fn skip_to_next<'a>(&self, device_id: impl Into<Option<&'a str>>) -> Result<(), ApiError> {
+        self.player_command(
+            Method::POST,
+            "https://api.spotify.com/v1/me/player/next",
+            &[],
+            device_id.into(),
+        )
+    }
+
+    pub fn skip_to_previous<'a>(
+        &self,
+        device_id: impl Into<Option<&'a str>>,
+    ) -> Result<(), ApiError> {
+        self.player_command(
+            Method::POST,
+            "https://api.spotify.com/v1/me/player/previous",
+            &[],
+            device_id.into(),
+        )
     }
 
-    pub fn resume_playback<'a>(&self, params: impl Into<Option<ResumePlayback<'a>>>) -> Response {
+    pub fn set_shuffle<'a>(
+        &self,
+        state: bool,
+        device_id: impl Into<Option<&'a str>>,
+    ) -> Result<(), ApiError> {
+        self.player_command(
+            Method::PUT,
+            "https://api.spotify.com/v1/me/player/shuffle",
+            &[("state", crate::bool_as_str(state))],
+            device_id.into(),
+        )
+    }
+
+    /// Transfers playback to `device_id`. With `play` set to `true` playback starts on the new
+    /// device, with `false` or `None` the current playback state is kept.
+    pub fn transfer_playback(
+        &self,
+        device_id: &str,
+        play: impl Into<Option<bool>>,
+    ) -> Result<(), ApiError> {
+        let body = TransferPlaybackBody {
+            device_ids: [device_id],
+            play: play.into(),
+        };
+
+        self.send(|authorization| {
+            attohttpc::put("https://api.spotify.com/v1/me/player")
+                .header(AUTHORIZATION, authorization)
+                .json(&body)?
+                .send()
+        })?;
+
+        Ok(())
+    }
+
+    /// Adds the track or episode `uri` to the end of the user's queue.
+    pub fn add_to_queue<'a>(
+        &self,
+        uri: &str,
+        device_id: impl Into<Option<&'a str>>,
+    ) -> Result<(), ApiError> {
+        self.player_command(
+            Method::POST,
+            "https://api.spotify.com/v1/me/player/queue",
+            &[("uri", uri)],
+            device_id.into(),
+        )
+    }
+
+    /// Sends a player command without body, its parameters in the query.
+    fn player_command(
+        &self,
+        method: Method,
+        url: &str,
+        params: &[(&str, &str)],
+        device_id: Option<&str>,
+    ) -> Result<(), ApiError> {
+        self.send(|authorization| {
+            let mut req = RequestBuilder::new(method.clone(), url)
+                .header(AUTHORIZATION, authorization)
+                .header(CONTENT_LENGTH, 0)
+                .params(params);
+
+            if let Some(device_id) = device_id {
+                req = req.param("device_id", device_id);
+            }
+
+            req.send()
+        })?;
+
+        Ok(())
+    }
+
+    pub fn resume_playback<'a>(
+        &self,
+        params: impl Into<Option<ResumePlayback<'a>>>,
+    ) -> Result<(), ApiError> {
         let params = params.into().unwrap_or_default();
+        let device_id = params.device_id;
+        let body = Into::<ResumePlaybackBody>::into(params);
 
-        let mut req = attohttpc::put("https://api.spotify.com/v1/me/player/play")
-            .header(AUTHORIZATION, &self.authorization_header);
+        self.send(|authorization| {
+            let mut req = attohttpc::put("https://api.spotify.com/v1/me/player/play")
+                .header(AUTHORIZATION, authorization);
 
-        if let Some(device_id) = params.device_id {
-            req = req.param("device_id", device_id);
-        }
+            if let Some(device_id) = device_id {
+                req = req.param("device_id", device_id);
+            }
 
-        req.json(&Into::<ResumePlaybackBody>::into(params))?.send()
+            req.json(&body)?.send()
+        })?;
+
+        Ok(())
     }
 }
 
@@ -112,15 +344,23 @@ impl<Scopes> Spotify<Scopes>
 where
     Scopes: Scoped<UserReadCurrentlyPlaying>,
 {
-    pub fn currently_playing<'a>(&self, market: impl Into<Option<&'a str>>) -> Response {
-        let mut req = attohttpc::get("https://api.spotify.com/v1/me/player/currently-playing")
-            .header(AUTHORIZATION, &self.authorization_header);
+    pub fn currently_playing<'a>(
+        &self,
+        market: impl Into<Option<&'a str>>,
+    ) -> Result<Option<CurrentlyPlaying>, ApiError> {
+        let market = market.into();
 
-        if let Some(market) = market.into() {
-            req = req.param("market", market);
-        }
+        self.send(|authorization| {
+            let mut req = attohttpc::get("https://api.spotify.com/v1/me/player/currently-playing")
+                .header(AUTHORIZATION, authorization);
 
-        req.send()
+            if let Some(market) = market {
+                req = req.param("market", market);
+            }
+
+            req.send()
+        })
+        .and_then(json_or_none)
     }
 }
 
@@ -128,14 +368,360 @@ impl<Scopes> Spotify<Scopes>
 where
     Scopes: Scoped<UserReadPlaybackState>,
 {
-    pub fn currently_playing_state<'a>(&self, market: impl Into<Option<&'a str>>) -> Response {
-        let mut req = attohttpc::get("https://api.spotify.com/v1/me/player/currently-playing")
-            .header(AUTHORIZATION, &self.authorization_header);
+    /// The playback state of the user's active device, or `None` when there is no active device.
+    pub fn playback_state<'a>(
+        &self,
+        market: impl Into<Option<&'a str>>,
+        additional_types: &[AdditionalType],
+    ) -> Result<Option<PlaybackState>, ApiError> {
+        let market = market.into();
+        let additional_types = AdditionalType::join(additional_types);
 
-        if let Some(market) = market.into() {
-            req = req.param("market", market);
-        }
+        self.send(|authorization| {
+            let mut req = attohttpc::get("https://api.spotify.com/v1/me/player")
+                .header(AUTHORIZATION, authorization);
+
+            if let Some(market) = market {
+                req = req.param("market", market);
+            }
+
+            if let Some(additional_types) = additional_types.as_ref() {
+                req = req.param("additional_types", additional_types);
+            }
+
+            req.send()
+        })
+        .and_then(json_or_none)
+    }
+
+    /// The user's available Spotify Connect devices.
+    pub fn devices(&self) -> Result<Vec<Device>, ApiError> {
+        let devices: Devices = self
+            .send(|authorization| {
+                attohttpc::get("https://api.spotify.com/v1/me/player/devices")
+                    .header(AUTHORIZATION, authorization)
+                    .send()
+            })?
+            .json_utf8()?;
+
+        Ok(devices.devices)
+    }
+
+    /// The currently playing item and the items queued after it.
+    pub fn queue(&self) -> Result<Queue, ApiError> {
+        Ok(self
+            .send(|authorization| {
+                attohttpc::get("https://api.spotify.com/v1/me/player/queue")
+                    .header(AUTHORIZATION, authorization)
+                    .send()
+            })?
+            .json_utf8()?)
+    }
+
+    /// The first available device named `name`.
+    pub fn device_by_name(&self, name: &str) -> Result<Option<Device>, ApiError> {
+        Ok(self
+            .devices()?
+            .into_iter()
+            .find(|device| device.name == name))
+    }
+}
+
+impl<Scopes> Spotify<Scopes>
+where
+    Scopes: Scoped<UserReadRecentlyPlayed>,
+{
+    /// Up to `limit` (at most 50) recently played tracks, most recent first. Spotify only keeps
+    /// the 50 most recent plays, which [`CursorPage::all`] iterates backward through.
+    pub fn recently_played(
+        &self,
+        limit: impl Into<Option<u32>>,
+        cursor: impl Into<Option<PlayedCursor>>,
+    ) -> Result<CursorPage<'_, Scopes, PlayHistory>, ApiError> {
+        let limit = limit.into();
+        let cursor = cursor.into();
+
+        let page = self
+            .send(|authorization| {
+                let mut req =
+                    attohttpc::get("https://api.spotify.com/v1/me/player/recently-played")
+                        .header(AUTHORIZATION, authorization);
+
+                if let Some(limit) = limit {
+                    req = req.param("limit", limit);
+                }
+
+                match cursor {
+                    Some(PlayedCursor::Before(before)) => req = req.param("before", before),
+                    Some(PlayedCursor::After(after)) => req = req.param("after", after),
+                    None => {}
+                }
+
+                req.send()
+            })?
+            .json_utf8()?;
+
+        Ok(CursorPage::new(self, page))
+    }
+}
+
+impl<Scopes> Spotify<Scopes>
+where
+    Scopes: Scoped<UserFollowRead>,
+{
+    /// The artists the user follows, in pages of `limit` (at most 50) artists starting after the
+    /// artist with the id `after`.
+    pub fn followed_artists<'a>(
+        &self,
+        limit: impl Into<Option<u32>>,
+        after: impl Into<Option<&'a str>>,
+    ) -> Result<CursorPage<'_, Scopes, Artist>, ApiError> {
+        let limit = limit.into();
+        let after = after.into();
+
+        let followed: FollowedArtists = self
+            .send(|authorization| {
+                let mut req = attohttpc::get("https://api.spotify.com/v1/me/following")
+                    .header(AUTHORIZATION, authorization)
+                    .param("type", "artist");
+
+                if let Some(limit) = limit {
+                    req = req.param("limit", limit);
+                }
+
+                if let Some(after) = after {
+                    req = req.param("after", after);
+                }
+
+                req.send()
+            })?
+            .json_utf8()?;
+
+        Ok(CursorPage::with_fetch(
+            self,
+            followed.artists,
+            |spotify, url| Ok(spotify.get::<FollowedArtists>(url)?.artists),
+        ))
+    }
+}
+
+impl<Scopes> Spotify<Scopes>
+where
+    Scopes: Scoped<UserTopRead>,
+{
+    /// The user's top artists by affinity. Spotify defaults to the medium term range and a limit
+    /// of 20, at most 50.
+    pub fn top_artists(
+        &self,
+        time_range: impl Into<Option<TimeRange>>,
+        limit: impl Into<Option<u32>>,
+        offset: impl Into<Option<u32>>,
+    ) -> Result<Page<'_, Scopes, Artist>, ApiError> {
+        self.top("artists", time_range.into(), limit.into(), offset.into())
+    }
+
+    /// The user's top tracks by affinity. Spotify defaults to the medium term range and a limit
+    /// of 20, at most 50.
+    pub fn top_tracks(
+        &self,
+        time_range: impl Into<Option<TimeRange>>,
+        limit: impl Into<Option<u32>>,
+        offset: impl Into<Option<u32>>,
+    ) -> Result<Page<'_, Scopes, Track>, ApiError> {
+        self.top("tracks", time_range.into(), limit.into(), offset.into())
+    }
+
+    fn top<T>(
+        &self,
+        kind: &str,
+        time_range: Option<TimeRange>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Page<'_, Scopes, T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        let url = format!("https://api.spotify.com/v1/me/top/{}", kind);
+        let params = top_params(time_range, limit, offset);
+
+        let page = self
+            .send(|authorization| {
+                attohttpc::get(&url)
+                    .header(AUTHORIZATION, authorization)
+                    .params(&params)
+                    .send()
+            })?
+            .json_utf8()?;
+
+        Ok(Page::new(self, page))
+    }
+}
+
+/// The query of the top artists and tracks endpoints, leaving out what Spotify defaults.
+fn top_params(
+    time_range: Option<TimeRange>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Vec<(&'static str, String)> {
+    let mut params = Vec::with_capacity(3);
+
+    if let Some(time_range) = time_range {
+        params.push(("time_range", time_range.as_str().to_owned()));
+    }
+
+    if let Some(limit) = limit {
+        params.push(("limit", limit.to_string()));
+    }
+
+    if let Some(offset) = offset {
+        params.push(("offset", offset.to_string()));
+    }
+
+    params
+}
+
+impl<Scopes> Spotify<ScopeList<Scopes>> {
+    /// The profile of the user who granted the token. Not available to handles with an app-only
+    /// token, which have no user.
+    pub fn current_user(&self) -> Result<PrivateUser<ScopeList<Scopes>>, ApiError> {
+        self.get("https://api.spotify.com/v1/me")
+    }
+}
+
+impl<Scopes> Spotify<Scopes> {
+    /// The public profile of the user with the id `user_id`.
+    pub fn user_profile(&self, user_id: &str) -> Result<PublicUser, ApiError> {
+        let mut url = Url::parse("https://api.spotify.com/v1/users").unwrap();
+        url.path_segments_mut().unwrap().push(user_id);
+
+        self.get(url.as_str())
+    }
+}
+
+impl<Scopes> Spotify<Scopes> {
+    /// Fetches and deserializes `url`, such as the `next` url of a page.
+    pub(crate) fn get<T>(&self, url: &str) -> Result<T, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(self
+            .send(|authorization| {
+                attohttpc::get(url)
+                    .header(AUTHORIZATION, authorization)
+                    .send()
+            })?
+            .json_utf8()?)
+    }
+}
+
+/// Deserializes the body of `response`, or returns `None` for `204 No Content` and empty bodies,
+/// which some endpoints respond with when there is nothing to report.
+fn json_or_none<T>(response: attohttpc::Response) -> Result<Option<T>, ApiError>
+where
+    T: DeserializeOwned,
+{
+    let body = response.bytes()?;
+
+    if body.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn joins_additional_types() {
+        assert_eq!(AdditionalType::join(&[]), None);
+        assert_eq!(
+            AdditionalType::join(&[AdditionalType::Track, AdditionalType::Episode]).as_deref(),
+            Some("track,episode")
+        );
+    }
+
+    #[test]
+    fn builds_top_params() {
+        assert!(top_params(None, None, None).is_empty());
+        assert_eq!(
+            top_params(Some(TimeRange::ShortTerm), None, Some(20)),
+            vec![
+                ("time_range", String::from("short_term")),
+                ("offset", String::from("20"))
+            ]
+        );
+        assert_eq!(
+            top_params(Some(TimeRange::LongTerm), Some(50), Some(0)),
+            vec![
+                ("time_range", String::from("long_term")),
+                ("limit", String::from("50")),
+                ("offset", String::from("0")),
+            ]
+        );
+    }
+
+    #[test]
+    fn transfer_playback_body() {
+        let body = TransferPlaybackBody {
+            device_ids: ["74ASZWbe4lXaubB36ztrGX"],
+            play: None,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&body).unwrap(),
+            r#"{"device_ids":["74ASZWbe4lXaubB36ztrGX"]}"#
+        );
+
+        let body = TransferPlaybackBody {
+            device_ids: ["74ASZWbe4lXaubB36ztrGX"],
+            play: Some(true),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&body).unwrap(),
+            r#"{"device_ids":["74ASZWbe4lXaubB36ztrGX"],"play":true}"#
+        );
+    }
+
+    #[test]
+    fn followed_artists() {
+        let artist: serde_json::Value =
+            serde_json::from_str(include_str!("model/fixtures/artist.json")).unwrap();
+
+        let followed: FollowedArtists = serde_json::from_value(serde_json::json!({
+            "artists": {
+                "href": "https://api.spotify.com/v1/me/following?type=artist&limit=1",
+                "items": [artist],
+                "limit": 1,
+                "next": "https://api.spotify.com/v1/me/following?type=artist&after=0TnOYISbd1XYRBk9myaseg&limit=1",
+                "cursors": {"after": "0TnOYISbd1XYRBk9myaseg"},
+                "total": 12,
+            }
+        }))
+        .unwrap();
+
+        let page = followed.artists;
+        assert_eq!(page.items[0].name, "Pitbull");
+        assert_eq!(page.total, Some(12));
+        assert_eq!(
+            page.cursors.unwrap().after.as_deref(),
+            Some("0TnOYISbd1XYRBk9myaseg")
+        );
+    }
+
+    #[test]
+    fn devices() {
+        let devices: Devices = serde_json::from_str(
+            r#"{"devices":[{"id":"5fbb3ba6aa454b5534c4ba43a8c7e8e45a63ad0e","is_active":false,"is_private_session":true,"is_restricted":false,"name":"My fridge","type":"Computer","volume_percent":100,"supports_volume":true}]}"#,
+        )
+        .unwrap();
 
-        req.send()
+        let fridge = &devices.devices[0];
+        assert_eq!(fridge.name, "My fridge");
+        assert_eq!(fridge.kind, crate::model::DeviceType::Computer);
+        assert!(fridge.is_private_session);
+        assert!(fridge.supports_volume);
     }
 }
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::error::TokenStoreError;
+use crate::model::Token;
+
+/// Persistent storage of tokens, keyed by user or client id.
+///
+/// A [`Spotify`](crate::Spotify) handle that refreshes its token can write refreshed tokens back
+/// to a store, see [`Spotify::token_store`](crate::Spotify::token_store).
+pub trait TokenStore: Send + Sync {
+    fn load(&self, key: &str) -> Result<Option<Token>, TokenStoreError>;
+
+    fn save(&self, key: &str, token: &Token) -> Result<(), TokenStoreError>;
+
+    fn delete(&self, key: &str) -> Result<(), TokenStoreError>;
+}
+
+/// Keeps tokens in memory for the lifetime of the store.
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: Mutex<HashMap<String, Token>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self, key: &str) -> Result<Option<Token>, TokenStoreError> {
+        Ok(self.tokens.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, key: &str, token: &Token) -> Result<(), TokenStoreError> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(key.to_owned(), token.clone());
+
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), TokenStoreError> {
+        self.tokens.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Keeps tokens in a single JSON file, an object mapping keys to tokens. The file is created on
+/// the first save, readable only by its owner on unix, and replaced atomically on every change.
+///
+/// The store is meant for a single process: every change rewrites the whole file, so concurrent
+/// changes from several processes sharing the file can undo each other.
+#[derive(Debug)]
+pub struct JsonFileTokenStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonFileTokenStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> Result<HashMap<String, Token>, TokenStoreError> {
+        match fs::read(&self.path) {
+            Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, tokens: &HashMap<String, Token>) -> Result<(), TokenStoreError> {
+        let contents = serde_json::to_vec(tokens)?;
+
+        // Unique per write, so that stores sharing the file never write the same temp file.
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let written = options
+            .open(&tmp)
+            .and_then(|mut file| file.write_all(&contents))
+            .and_then(|_| fs::rename(&tmp, &self.path));
+
+        if written.is_err() {
+            fs::remove_file(&tmp).ok();
+        }
+
+        Ok(written?)
+    }
+}
+
+impl TokenStore for JsonFileTokenStore {
+    fn load(&self, key: &str) -> Result<Option<Token>, TokenStoreError> {
+        let _lock = self.lock.lock().unwrap();
+
+        Ok(self.read()?.remove(key))
+    }
+
+    fn save(&self, key: &str, token: &Token) -> Result<(), TokenStoreError> {
+        let _lock = self.lock.lock().unwrap();
+
+        let mut tokens = self.read()?;
+        tokens.insert(key.to_owned(), token.clone());
+        self.write(&tokens)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), TokenStoreError> {
+        let _lock = self.lock.lock().unwrap();
+
+        let mut tokens = self.read()?;
+
+        if tokens.remove(key).is_some() {
+            self.write(&tokens)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn token(access_token: &str) -> Token {
+        Token {
+            access_token: String::from(access_token),
+            token_type: String::from("Bearer"),
+            scope: String::from("user-read-email"),
+            expires_in: 3600,
+            refresh_token: Some(String::from("wefwfjwoeif")),
+            obtained_at: UNIX_EPOCH + Duration::from_secs(1577836800),
+        }
+    }
+
+    fn exercise(store: &dyn TokenStore) {
+        assert!(store.load("alice").unwrap().is_none());
+
+        store.save("alice", &token("reojwgpoerjg")).unwrap();
+        store.save("bob", &token("flkejrflwef")).unwrap();
+
+        let alice = store.load("alice").unwrap().unwrap();
+        assert_eq!(alice.access_token, "reojwgpoerjg");
+        assert_eq!(alice.refresh_token.as_deref(), Some("wefwfjwoeif"));
+        assert_eq!(alice.obtained_at, token("").obtained_at);
+
+        store.save("alice", &token("oijfsklj")).unwrap();
+        assert_eq!(
+            store.load("alice").unwrap().unwrap().access_token,
+            "oijfsklj"
+        );
+
+        store.delete("alice").unwrap();
+        store.delete("carol").unwrap();
+        assert!(store.load("alice").unwrap().is_none());
+        assert_eq!(
+            store.load("bob").unwrap().unwrap().access_token,
+            "flkejrflwef"
+        );
+    }
+
+    #[test]
+    fn memory_store() {
+        exercise(&MemoryTokenStore::new());
+    }
+
+    #[test]
+    fn json_file_store() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        let path = std::env::temp_dir().join(format!("spotify-web-tokens-{}.json", nanos));
+
+        exercise(&JsonFileTokenStore::new(&path));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let reopened = JsonFileTokenStore::new(&path);
+        assert_eq!(
+            reopened.load("bob").unwrap().unwrap().access_token,
+            "flkejrflwef"
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+}